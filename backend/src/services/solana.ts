@@ -47,9 +47,12 @@ export class SolanaService {
   }
 
   // Derive PDA for salon
-  getSalonPDA(ownerPubkey: PublicKey): [PublicKey, number] {
+  getSalonPDA(ownerPubkey: PublicKey, locationIndex: number = 0): [PublicKey, number] {
+    const indexBuffer = Buffer.alloc(2);
+    indexBuffer.writeUInt16LE(locationIndex);
+
     return PublicKey.findProgramAddressSync(
-      [Buffer.from('salon'), ownerPubkey.toBuffer()],
+      [Buffer.from('salon'), ownerPubkey.toBuffer(), indexBuffer],
       this.programId
     );
   }
@@ -1,17 +1,80 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::ops::Range;
+
+mod math;
 
 declare_id!("So1Book111111111111111111111111111111111111");
 
 // Constants for refund calculations (in lamports, 1 SOL = 1_000_000_000 lamports)
-// Using approximate EUR to SOL conversion (assuming 1 EUR ≈ 0.05 SOL for devnet testing)
-pub const EUR_TO_LAMPORTS: u64 = 50_000_000; // 0.05 SOL per EUR
-pub const APP_COMMISSION_BPS: u64 = 300; // 3% = 300 basis points
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+// Platform defaults, adjustable with update_platform_config
+pub const DEFAULT_COMMISSION_BPS: u16 = 300; // 3% = 300 basis points
+pub const DEFAULT_NO_SHOW_GRACE_SECS: i64 = 15 * 60;
+pub const MAX_COMMISSION_BPS: u16 = 2_000; // 20%
+pub const MAX_NO_SHOW_GRACE_SECS: i64 = 24 * 60 * 60;
+pub const DEFAULT_SETTLEMENT_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+pub const MAX_SETTLEMENT_WINDOW_SECS: i64 = 90 * 24 * 60 * 60;
+pub const DEFAULT_CRANK_REWARD_BPS: u16 = 1_000; // 10% of the commission
+pub const DEFAULT_DISPUTE_WINDOW_SECS: i64 = 48 * 60 * 60;
+pub const MAX_DISPUTE_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+
+// Pause bitmask for Platform.paused_operations. Client cancellations are never paused.
+pub const PAUSE_BOOKINGS: u8 = 1 << 0;
+pub const PAUSE_SALON_REGISTRATION: u8 = 1 << 1;
+pub const PAUSE_PAYOUTS: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_BOOKINGS | PAUSE_SALON_REGISTRATION | PAUSE_PAYOUTS;
+
+// Pyth v2 price account layout (only the fields we read)
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_VERSION: u32 = 2;
+pub const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+// Salon catalog limits
+pub const MAX_SERVICES: usize = 10;
+pub const MAX_SERVICE_NAME_LEN: usize = 32;
+pub const MAX_DELEGATES: usize = 8;
+pub const MAX_LINE_ITEMS: usize = 5;
 
 // Time thresholds in seconds
 pub const HOURS_48: i64 = 48 * 60 * 60;
 pub const HOURS_24: i64 = 24 * 60 * 60;
 
+// Booking slots: each salon day is split into 15 minute slots (UTC days)
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+pub const SLOT_SECONDS: i64 = 15 * 60;
+pub const SLOTS_PER_DAY: usize = 96;
+
+// Business hours
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+pub const MAX_CLOSURES: usize = 16;
+pub const MIN_UTC_OFFSET_MINUTES: i16 = -12 * 60;
+pub const MAX_UTC_OFFSET_MINUTES: i16 = 14 * 60;
+
+// Cancellation policy limits
+pub const MAX_CANCELLATION_TIERS: usize = 5;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// `SettlementAccounts` of an instruction whose accounts use the usual payout names
+macro_rules! settlement_accounts {
+    ($accounts:expr) => {
+        SettlementAccounts {
+            reservation: &$accounts.reservation,
+            escrow_token_account: $accounts.escrow_token_account.as_ref(),
+            token_program: $accounts.token_program.as_ref(),
+            payout_wallet: &$accounts.payout_wallet,
+            salon_token_account: $accounts.salon_token_account.as_ref(),
+            staff_wallet: $accounts.staff_wallet.as_ref(),
+            staff_token_account: $accounts.staff_token_account.as_ref(),
+            treasury: &$accounts.treasury,
+            treasury_token_account: $accounts.treasury_token_account.as_ref(),
+        }
+    };
+}
+
 #[program]
 pub mod solbook {
     use super::*;
@@ -24,8 +87,20 @@ pub mod solbook {
         let platform = &mut ctx.accounts.platform;
         platform.admin = ctx.accounts.admin.key();
         platform.treasury_wallet = treasury_wallet;
+        platform.pending_admin = None;
+        platform.paused_operations = 0;
         platform.total_reservations = 0;
         platform.total_volume = 0;
+        platform.commission_bps = DEFAULT_COMMISSION_BPS;
+        platform.no_show_grace_secs = DEFAULT_NO_SHOW_GRACE_SECS;
+        platform.default_cancellation_policy = CancellationPolicy::default();
+        platform.settlement_window_secs = DEFAULT_SETTLEMENT_WINDOW_SECS;
+        platform.expired_settlement = ExpiredSettlement::Complete;
+        platform.crank_reward_bps = DEFAULT_CRANK_REWARD_BPS;
+        platform.dispute_window_secs = DEFAULT_DISPUTE_WINDOW_SECS;
+        platform.arbiter = ctx.accounts.admin.key();
+        platform.auto_close_reservations = false;
+        platform.tip_fee_bps = 0;
         platform.bump = ctx.bumps.platform;
 
         emit!(PlatformInitialized {
@@ -36,21 +111,207 @@ pub mod solbook {
         Ok(())
     }
 
+    /// Update commission, grace and settlement periods and default policies (by admin)
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatform>,
+        params: PlatformConfigParams,
+    ) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+
+        if let Some(commission_bps) = params.commission_bps {
+            require!(
+                commission_bps <= MAX_COMMISSION_BPS,
+                GlamBookError::CommissionTooHigh
+            );
+            platform.commission_bps = commission_bps;
+        }
+        if let Some(no_show_grace_secs) = params.no_show_grace_secs {
+            require!(
+                (0..=MAX_NO_SHOW_GRACE_SECS).contains(&no_show_grace_secs),
+                GlamBookError::InvalidPlatformConfig
+            );
+            platform.no_show_grace_secs = no_show_grace_secs;
+        }
+        if let Some(policy) = params.default_cancellation_policy {
+            policy.validate()?;
+            platform.default_cancellation_policy = policy;
+        }
+        if let Some(settlement_window_secs) = params.settlement_window_secs {
+            require!(
+                (0..=MAX_SETTLEMENT_WINDOW_SECS).contains(&settlement_window_secs),
+                GlamBookError::InvalidPlatformConfig
+            );
+            platform.settlement_window_secs = settlement_window_secs;
+        }
+        if let Some(expired_settlement) = params.expired_settlement {
+            platform.expired_settlement = expired_settlement;
+        }
+        if let Some(crank_reward_bps) = params.crank_reward_bps {
+            require!(
+                crank_reward_bps as u64 <= BPS_DENOMINATOR,
+                GlamBookError::InvalidPlatformConfig
+            );
+            platform.crank_reward_bps = crank_reward_bps;
+        }
+        if let Some(dispute_window_secs) = params.dispute_window_secs {
+            require!(
+                (0..=MAX_DISPUTE_WINDOW_SECS).contains(&dispute_window_secs),
+                GlamBookError::InvalidPlatformConfig
+            );
+            platform.dispute_window_secs = dispute_window_secs;
+        }
+        if let Some(auto_close_reservations) = params.auto_close_reservations {
+            platform.auto_close_reservations = auto_close_reservations;
+        }
+        if let Some(tip_fee_bps) = params.tip_fee_bps {
+            require!(
+                tip_fee_bps <= MAX_COMMISSION_BPS,
+                GlamBookError::CommissionTooHigh
+            );
+            platform.tip_fee_bps = tip_fee_bps;
+        }
+
+        emit!(PlatformConfigUpdated {
+            commission_bps: platform.commission_bps,
+            no_show_grace_secs: platform.no_show_grace_secs,
+            default_cancellation_policy: platform.default_cancellation_policy.clone(),
+            settlement_window_secs: platform.settlement_window_secs,
+            expired_settlement: platform.expired_settlement.clone(),
+            crank_reward_bps: platform.crank_reward_bps,
+            dispute_window_secs: platform.dispute_window_secs,
+            auto_close_reservations: platform.auto_close_reservations,
+            tip_fee_bps: platform.tip_fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new admin; the handover completes once they call accept_admin (by admin).
+    /// Proposing the default pubkey withdraws a pending proposal.
+    pub fn propose_admin(ctx: Context<UpdatePlatform>, new_admin: Pubkey) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        platform.pending_admin = (new_admin != Pubkey::default()).then_some(new_admin);
+
+        emit!(AdminTransferProposed {
+            admin: platform.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending admin handover (by the proposed admin)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        let previous_admin = platform.admin;
+        platform.admin = ctx.accounts.new_admin.key();
+        platform.pending_admin = None;
+
+        emit!(AdminTransferred {
+            previous_admin,
+            new_admin: platform.admin,
+        });
+
+        Ok(())
+    }
+
+    /// Rotate the wallet that receives platform commission (by admin)
+    pub fn set_treasury(ctx: Context<UpdatePlatform>, treasury_wallet: Pubkey) -> Result<()> {
+        require!(
+            treasury_wallet != Pubkey::default(),
+            GlamBookError::InvalidTreasury
+        );
+
+        let platform = &mut ctx.accounts.platform;
+        let previous_treasury = platform.treasury_wallet;
+        platform.treasury_wallet = treasury_wallet;
+
+        emit!(TreasuryUpdated {
+            previous_treasury,
+            treasury_wallet,
+        });
+
+        Ok(())
+    }
+
+    /// Appoint the wallet that resolves disputes (by admin)
+    pub fn set_arbiter(ctx: Context<UpdatePlatform>, arbiter: Pubkey) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+        platform.arbiter = arbiter;
+
+        emit!(ArbiterUpdated { arbiter });
+
+        Ok(())
+    }
+
+    /// Pause or resume bookings, salon registration and salon payouts (by admin).
+    /// Clients can always cancel and recover their escrow.
+    pub fn set_paused_operations(
+        ctx: Context<UpdatePlatform>,
+        paused_operations: u8,
+    ) -> Result<()> {
+        require!(
+            paused_operations & !PAUSE_ALL == 0,
+            GlamBookError::InvalidPlatformConfig
+        );
+
+        let platform = &mut ctx.accounts.platform;
+        platform.paused_operations = paused_operations;
+
+        emit!(PauseUpdated { paused_operations });
+
+        Ok(())
+    }
+
     /// Register a new salon on the platform
     pub fn register_salon(
         ctx: Context<RegisterSalon>,
         name: String,
         services: Vec<ServiceInput>,
     ) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_SALON_REGISTRATION)?;
         require!(name.len() <= 64, GlamBookError::NameTooLong);
-        require!(services.len() <= 10, GlamBookError::TooManyServices);
+        require!(services.len() <= MAX_SERVICES, GlamBookError::TooManyServices);
+        for (index, service) in services.iter().enumerate() {
+            require!(
+                service.name.len() <= MAX_SERVICE_NAME_LEN,
+                GlamBookError::ServiceNameTooLong
+            );
+            require!(
+                !services[..index].iter().any(|s| s.id == service.id),
+                GlamBookError::DuplicateServiceId
+            );
+        }
+
+        // Each location of an owner takes the next index in its registry
+        let owner_registry = &mut ctx.accounts.owner_registry;
+        if owner_registry.wallet == Pubkey::default() {
+            owner_registry.wallet = ctx.accounts.owner.key();
+            owner_registry.bump = ctx.bumps.owner_registry;
+        }
+        let location_index = owner_registry.location_count;
+        owner_registry.location_count = location_index
+            .checked_add(1)
+            .ok_or(GlamBookError::MathOverflow)?;
 
         let salon = &mut ctx.accounts.salon;
         salon.owner = ctx.accounts.owner.key();
+        salon.payout_wallet = ctx.accounts.owner.key();
+        salon.location_index = location_index;
         salon.name = name.clone();
         salon.is_active = true;
         salon.total_earnings = 0;
         salon.reservation_count = 0;
+        salon.payment_mint = None;
+        salon.cancellation_policy = ctx.accounts.platform.default_cancellation_policy.clone();
+        salon.chairs = 1;
+        salon.has_business_hours = false;
+        salon.reschedule_policy = ReschedulePolicy::default();
+        salon.bond_lamports = 0;
+        salon.salon_cancellations = 0;
+        salon.delegates = Vec::new();
+        salon.utc_offset_minutes = 0;
+        salon.deposit_bps = BPS_DENOMINATOR as u16;
         salon.bump = ctx.bumps.salon;
 
         // Store services
@@ -68,349 +329,3374 @@ pub mod solbook {
         emit!(SalonRegistered {
             salon: salon.key(),
             owner: salon.owner,
+            location_index,
             name,
         });
 
         Ok(())
     }
 
-    /// Create a new reservation with payment
-    pub fn create_reservation(
-        ctx: Context<CreateReservation>,
-        service_id: u8,
-        appointment_time: i64,
-    ) -> Result<()> {
-        let salon = &ctx.accounts.salon;
-        let clock = Clock::get()?;
-        
-        // Validate appointment time is in the future
+    /// Add a service to the salon catalog (by salon owner)
+    pub fn add_service(ctx: Context<UpdateSalon>, service: ServiceInput) -> Result<()> {
+        let salon = &mut ctx.accounts.salon;
+
         require!(
-            appointment_time > clock.unix_timestamp,
-            GlamBookError::InvalidAppointmentTime
+            salon.services.len() < MAX_SERVICES,
+            GlamBookError::TooManyServices
+        );
+        require!(
+            service.name.len() <= MAX_SERVICE_NAME_LEN,
+            GlamBookError::ServiceNameTooLong
+        );
+        require!(
+            !salon.services.iter().any(|s| s.id == service.id),
+            GlamBookError::DuplicateServiceId
         );
 
-        // Find the service and get its price
+        salon.services.push(Service {
+            id: service.id,
+            name: service.name.clone(),
+            price_lamports: service.price_lamports,
+            duration_minutes: service.duration_minutes,
+            is_active: true,
+        });
+
+        emit!(ServiceAdded {
+            salon: salon.key(),
+            service_id: service.id,
+            name: service.name,
+            price_lamports: service.price_lamports,
+            duration_minutes: service.duration_minutes,
+        });
+
+        Ok(())
+    }
+
+    /// Update name, price, duration or active flag of an existing service (by salon owner)
+    pub fn update_service(
+        ctx: Context<UpdateSalon>,
+        service_id: u8,
+        update: ServiceUpdate,
+    ) -> Result<()> {
+        let salon = &mut ctx.accounts.salon;
+        let salon_key = salon.key();
+
         let service = salon
             .services
-            .iter()
-            .find(|s| s.id == service_id && s.is_active)
+            .iter_mut()
+            .find(|s| s.id == service_id)
             .ok_or(GlamBookError::ServiceNotFound)?;
 
-        let amount = service.price_lamports;
+        if let Some(name) = update.name {
+            require!(
+                name.len() <= MAX_SERVICE_NAME_LEN,
+                GlamBookError::ServiceNameTooLong
+            );
+            service.name = name;
+        }
+        if let Some(price_lamports) = update.price_lamports {
+            service.price_lamports = price_lamports;
+        }
+        if let Some(duration_minutes) = update.duration_minutes {
+            service.duration_minutes = duration_minutes;
+        }
+        if let Some(is_active) = update.is_active {
+            service.is_active = is_active;
+        }
 
-        // Transfer payment from client to escrow (reservation PDA)
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.client.to_account_info(),
-                to: ctx.accounts.reservation.to_account_info(),
-            },
-        );
-        system_program::transfer(cpi_context, amount)?;
+        // Existing reservations keep the price and name captured at booking time
+        emit!(ServiceUpdated {
+            salon: salon_key,
+            service_id,
+            name: service.name.clone(),
+            price_lamports: service.price_lamports,
+            duration_minutes: service.duration_minutes,
+            is_active: service.is_active,
+        });
 
-        // Initialize reservation account
-        let reservation = &mut ctx.accounts.reservation;
-        reservation.client = ctx.accounts.client.key();
-        reservation.salon = salon.key();
-        reservation.salon_owner = salon.owner;
-        reservation.service_id = service_id;
-        reservation.service_name = service.name.clone();
-        reservation.amount = amount;
-        reservation.appointment_time = appointment_time;
-        reservation.created_at = clock.unix_timestamp;
-        reservation.status = ReservationStatus::Confirmed;
-        reservation.bump = ctx.bumps.reservation;
+        Ok(())
+    }
 
-        // Update platform stats
-        let platform = &mut ctx.accounts.platform;
-        platform.total_reservations += 1;
-        platform.total_volume += amount;
+    /// Remove a service from the salon catalog (by salon owner)
+    pub fn remove_service(ctx: Context<UpdateSalon>, service_id: u8) -> Result<()> {
+        let salon = &mut ctx.accounts.salon;
 
-        // Update salon stats
-        let salon_mut = &mut ctx.accounts.salon;
-        salon_mut.reservation_count += 1;
+        let index = salon
+            .services
+            .iter()
+            .position(|s| s.id == service_id)
+            .ok_or(GlamBookError::ServiceNotFound)?;
+        salon.services.remove(index);
 
-        emit!(ReservationCreated {
-            reservation: reservation.key(),
-            client: reservation.client,
-            salon: reservation.salon,
+        emit!(ServiceRemoved {
+            salon: salon.key(),
             service_id,
-            amount,
-            appointment_time,
         });
 
         Ok(())
     }
 
-    /// Cancel a reservation and process refund
-    pub fn cancel_reservation(ctx: Context<CancelReservation>) -> Result<()> {
-        let reservation = &mut ctx.accounts.reservation;
-        let clock = Clock::get()?;
+    /// Replace the salon's cancellation policy (by salon owner).
+    /// Existing reservations keep the policy captured at booking time.
+    pub fn set_cancellation_policy(
+        ctx: Context<UpdateSalon>,
+        policy: CancellationPolicy,
+    ) -> Result<()> {
+        policy.validate()?;
 
-        // Only client can cancel their own reservation
-        require!(
-            ctx.accounts.client.key() == reservation.client,
-            GlamBookError::UnauthorizedCancellation
-        );
+        let salon = &mut ctx.accounts.salon;
+        salon.cancellation_policy = policy.clone();
 
-        // Can only cancel confirmed reservations
-        require!(
-            reservation.status == ReservationStatus::Confirmed,
-            GlamBookError::InvalidReservationStatus
-        );
+        emit!(CancellationPolicyUpdated {
+            salon: salon.key(),
+            policy,
+        });
 
-        let time_until_appointment = reservation.appointment_time - clock.unix_timestamp;
-        
-        // Calculate refund based on cancellation policy
-        let (client_refund, salon_fee, app_commission) = 
-            calculate_refund(reservation.amount, time_until_appointment)?;
+        Ok(())
+    }
 
-        // Update reservation status
-        reservation.status = ReservationStatus::Cancelled;
-        reservation.cancelled_at = Some(clock.unix_timestamp);
+    /// Set how many clients the salon can serve at the same time (by salon owner)
+    pub fn set_chairs(ctx: Context<UpdateSalon>, chairs: u8) -> Result<()> {
+        require!(chairs > 0, GlamBookError::InvalidChairCount);
 
-        // Transfer refund to client
-        if client_refund > 0 {
-            **ctx.accounts.reservation.to_account_info().try_borrow_mut_lamports()? -= client_refund;
-            **ctx.accounts.client.to_account_info().try_borrow_mut_lamports()? += client_refund;
-        }
+        let salon = &mut ctx.accounts.salon;
+        salon.chairs = chairs;
 
-        // Transfer fee to salon owner
-        if salon_fee > 0 {
-            **ctx.accounts.reservation.to_account_info().try_borrow_mut_lamports()? -= salon_fee;
-            **ctx.accounts.salon_owner.to_account_info().try_borrow_mut_lamports()? += salon_fee;
-        }
+        emit!(SalonCapacityUpdated {
+            salon: salon.key(),
+            chairs,
+        });
 
-        // Transfer commission to platform treasury
-        if app_commission > 0 {
-            **ctx.accounts.reservation.to_account_info().try_borrow_mut_lamports()? -= app_commission;
-            **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += app_commission;
-        }
+        Ok(())
+    }
 
-        emit!(ReservationCancelled {
-            reservation: reservation.key(),
-            client: reservation.client,
-            refund_amount: client_refund,
-            salon_fee,
-            app_commission,
-            time_until_appointment,
-        });
+    /// Set how late and at what fee clients may move a booking (by salon owner)
+    pub fn set_reschedule_policy(
+        ctx: Context<UpdateSalon>,
+        policy: ReschedulePolicy,
+    ) -> Result<()> {
+        require!(
+            policy.min_notice_secs >= 0 && policy.fee_bps as u64 <= BPS_DENOMINATOR,
+            GlamBookError::InvalidReschedulePolicy
+        );
 
-        emit!(RefundProcessed {
-            reservation: reservation.key(),
-            client: reservation.client,
-            amount: client_refund,
+        let salon = &mut ctx.accounts.salon;
+        salon.reschedule_policy = policy.clone();
+
+        emit!(ReschedulePolicyUpdated {
+            salon: salon.key(),
+            policy,
         });
 
         Ok(())
     }
 
-    /// Mark reservation as completed (by salon owner)
-    pub fn complete_reservation(ctx: Context<CompleteReservation>) -> Result<()> {
-        let reservation = &mut ctx.accounts.reservation;
-        let clock = Clock::get()?;
-
-        // Only salon owner can complete
+    /// Require only a deposit at booking; the rest is paid at the venue or with
+    /// `pay_balance` (by salon owner). Applies to new bookings only.
+    pub fn set_deposit_bps(ctx: Context<UpdateSalon>, deposit_bps: u16) -> Result<()> {
         require!(
-            ctx.accounts.salon_owner.key() == reservation.salon_owner,
-            GlamBookError::UnauthorizedCompletion
+            deposit_bps > 0 && deposit_bps as u64 <= BPS_DENOMINATOR,
+            GlamBookError::InvalidDeposit
         );
 
-        // Must be confirmed status
-        require!(
-            reservation.status == ReservationStatus::Confirmed,
-            GlamBookError::InvalidReservationStatus
-        );
+        let salon = &mut ctx.accounts.salon;
+        salon.deposit_bps = deposit_bps;
+
+        emit!(DepositPolicyUpdated {
+            salon: salon.key(),
+            deposit_bps,
+        });
+
+        Ok(())
+    }
 
-        // Can only complete after appointment time
+    /// Send future salon payouts to another wallet, e.g. a cold wallet or multisig (by salon owner).
+    /// Held payouts are paid to the wallet set when they are released.
+    pub fn set_payout_wallet(ctx: Context<UpdateSalon>, payout_wallet: Pubkey) -> Result<()> {
         require!(
-            clock.unix_timestamp >= reservation.appointment_time,
-            GlamBookError::AppointmentNotYetDue
+            payout_wallet != Pubkey::default(),
+            GlamBookError::InvalidPayoutWallet
         );
 
-        let amount = reservation.amount;
-        let app_commission = (amount * APP_COMMISSION_BPS) / 10000;
-        let salon_payment = amount - app_commission;
+        let salon = &mut ctx.accounts.salon;
+        salon.payout_wallet = payout_wallet;
 
-        reservation.status = ReservationStatus::Completed;
-        reservation.completed_at = Some(clock.unix_timestamp);
+        emit!(PayoutWalletUpdated {
+            salon: salon.key(),
+            payout_wallet,
+        });
 
-        // Transfer payment to salon owner
-        **ctx.accounts.reservation.to_account_info().try_borrow_mut_lamports()? -= salon_payment;
-        **ctx.accounts.salon_owner.to_account_info().try_borrow_mut_lamports()? += salon_payment;
+        Ok(())
+    }
 
-        // Transfer commission to platform treasury
-        **ctx.accounts.reservation.to_account_info().try_borrow_mut_lamports()? -= app_commission;
-        **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += app_commission;
+    /// Add a stylist to the salon (by salon owner)
+    pub fn add_staff(ctx: Context<AddStaff>, wallet: Pubkey, staff_input: StaffInput) -> Result<()> {
+        let staff = &mut ctx.accounts.staff;
+        staff.salon = ctx.accounts.salon.key();
+        staff.wallet = wallet;
+        staff.name = staff_input.name;
+        staff.service_ids = staff_input.service_ids;
+        staff.commission_split_bps = staff_input.commission_split_bps;
+        staff.capacity = staff_input.capacity;
+        staff.is_active = true;
+        staff.bump = ctx.bumps.staff;
+        staff.validate()?;
 
-        // Update salon earnings
-        let salon = &mut ctx.accounts.salon;
-        salon.total_earnings += salon_payment;
+        emit!(StaffUpdated {
+            salon: staff.salon,
+            staff: staff.key(),
+            wallet,
+            service_ids: staff.service_ids.clone(),
+            commission_split_bps: staff.commission_split_bps,
+            capacity: staff.capacity,
+            is_active: true,
+        });
 
-        emit!(ReservationCompleted {
-            reservation: reservation.key(),
-            salon_payment,
-            app_commission,
+        Ok(())
+    }
+
+    /// Update a stylist's services, split, capacity or active flag (by salon owner).
+    /// Existing reservations keep the split captured at booking time.
+    pub fn update_staff(ctx: Context<UpdateStaff>, update: StaffUpdate) -> Result<()> {
+        let staff = &mut ctx.accounts.staff;
+
+        if let Some(name) = update.name {
+            staff.name = name;
+        }
+        if let Some(service_ids) = update.service_ids {
+            staff.service_ids = service_ids;
+        }
+        if let Some(commission_split_bps) = update.commission_split_bps {
+            staff.commission_split_bps = commission_split_bps;
+        }
+        if let Some(capacity) = update.capacity {
+            staff.capacity = capacity;
+        }
+        if let Some(is_active) = update.is_active {
+            staff.is_active = is_active;
+        }
+        staff.validate()?;
+
+        emit!(StaffUpdated {
+            salon: staff.salon,
+            staff: staff.key(),
+            wallet: staff.wallet,
+            service_ids: staff.service_ids.clone(),
+            commission_split_bps: staff.commission_split_bps,
+            capacity: staff.capacity,
+            is_active: staff.is_active,
         });
 
         Ok(())
     }
 
-    /// Mark reservation as no-show (by salon owner after appointment time)
-    pub fn mark_no_show(ctx: Context<MarkNoShow>) -> Result<()> {
-        let reservation = &mut ctx.accounts.reservation;
-        let clock = Clock::get()?;
+    /// Remove a stylist and return the account rent (by salon owner).
+    /// Their existing reservations are still paid out to the stored wallet.
+    pub fn remove_staff(ctx: Context<RemoveStaff>) -> Result<()> {
+        emit!(StaffRemoved {
+            salon: ctx.accounts.salon.key(),
+            staff: ctx.accounts.staff.key(),
+            wallet: ctx.accounts.staff.wallet,
+        });
+
+        Ok(())
+    }
 
-        // Only salon owner can mark no-show
+    /// Let another wallet operate the salon front desk (by salon owner, or by a
+    /// manager for receptionists)
+    pub fn add_delegate(
+        ctx: Context<ManageDelegates>,
+        wallet: Pubkey,
+        role: SalonRole,
+    ) -> Result<()> {
+        let salon = &mut ctx.accounts.salon;
+        let authority = ctx.accounts.authority.key();
+
+        require!(role != SalonRole::Owner, GlamBookError::InvalidSalonRole);
         require!(
-            ctx.accounts.salon_owner.key() == reservation.salon_owner,
-            GlamBookError::UnauthorizedNoShow
+            salon.can_manage_role(&authority, &role),
+            GlamBookError::UnauthorizedDelegateChange
         );
-
-        // Must be confirmed status
         require!(
-            reservation.status == ReservationStatus::Confirmed,
-            GlamBookError::InvalidReservationStatus
+            salon.role_of(&wallet).is_none(),
+            GlamBookError::DuplicateDelegate
         );
-
-        // Can only mark no-show after appointment time + 15 min grace period
         require!(
-            clock.unix_timestamp >= reservation.appointment_time + (15 * 60),
-            GlamBookError::TooEarlyForNoShow
+            salon.delegates.len() < MAX_DELEGATES,
+            GlamBookError::TooManyDelegates
         );
 
-        let amount = reservation.amount;
-        // No-show: 0% refund, 100% to salon minus commission
-        let app_commission = (amount * APP_COMMISSION_BPS) / 10000;
-        let salon_payment = amount - app_commission;
-
-        reservation.status = ReservationStatus::NoShow;
+        salon.delegates.push(SalonDelegate {
+            wallet,
+            role: role.clone(),
+        });
 
-        // Transfer payment to salon owner
-        **ctx.accounts.reservation.to_account_info().try_borrow_mut_lamports()? -= salon_payment;
-        **ctx.accounts.salon_owner.to_account_info().try_borrow_mut_lamports()? += salon_payment;
+        emit!(DelegateAdded {
+            salon: salon.key(),
+            wallet,
+            role,
+            added_by: authority,
+        });
 
-        // Transfer commission to platform treasury
-        **ctx.accounts.reservation.to_account_info().try_borrow_mut_lamports()? -= app_commission;
-        **ctx.accounts.treasury.to_account_info().try_borrow_mut_lamports()? += app_commission;
+        Ok(())
+    }
 
-        // Update salon earnings
+    /// Revoke a delegate (by salon owner, or by a manager for receptionists)
+    pub fn remove_delegate(ctx: Context<ManageDelegates>, wallet: Pubkey) -> Result<()> {
         let salon = &mut ctx.accounts.salon;
-        salon.total_earnings += salon_payment;
+        let authority = ctx.accounts.authority.key();
 
-        emit!(ReservationNoShow {
+        let index = salon
+            .delegates
+            .iter()
+            .position(|delegate| delegate.wallet == wallet)
+            .ok_or(GlamBookError::DelegateNotFound)?;
+        let role = salon.delegates[index].role.clone();
+        require!(
+            salon.can_manage_role(&authority, &role),
+            GlamBookError::UnauthorizedDelegateChange
+        );
+        salon.delegates.remove(index);
+
+        emit!(DelegateRemoved {
+            salon: salon.key(),
+            wallet,
+            role,
+            removed_by: authority,
+        });
+
+        Ok(())
+    }
+
+    /// Lock SOL in the salon account to fund compensation for salon cancellations
+    pub fn deposit_bond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.salon.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, amount)?;
+
+        let salon = &mut ctx.accounts.salon;
+        salon.bond_lamports = math::add(salon.bond_lamports, amount)?;
+
+        emit!(BondUpdated {
+            salon: salon.key(),
+            bond_lamports: salon.bond_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Take unused bond back to the owner wallet
+    pub fn withdraw_bond(ctx: Context<ManageBond>, amount: u64) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_PAYOUTS)?;
+
+        let salon = &mut ctx.accounts.salon;
+        require!(amount <= salon.bond_lamports, GlamBookError::InsufficientBond);
+
+        salon.bond_lamports = math::sub(salon.bond_lamports, amount)?;
+        transfer_out(
+            &salon.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            Some(amount),
+        )?;
+
+        emit!(BondUpdated {
+            salon: salon.key(),
+            bond_lamports: salon.bond_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Set weekly opening hours in the salon's local time (by salon owner).
+    /// Once set, bookings outside these hours or inside closures are rejected.
+    pub fn set_business_hours(
+        ctx: Context<SetBusinessHours>,
+        utc_offset_minutes: i16,
+        weekly: [DayHours; 7],
+    ) -> Result<()> {
+        require!(
+            (MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&utc_offset_minutes),
+            GlamBookError::InvalidBusinessHours
+        );
+        for day in weekly.iter() {
+            require!(
+                day.open_minute <= day.close_minute && day.close_minute <= MINUTES_PER_DAY,
+                GlamBookError::InvalidBusinessHours
+            );
+        }
+
+        let salon = &mut ctx.accounts.salon;
+        salon.has_business_hours = true;
+        salon.utc_offset_minutes = utc_offset_minutes;
+
+        let salon_hours = &mut ctx.accounts.salon_hours;
+        salon_hours.salon = salon.key();
+        salon_hours.utc_offset_minutes = utc_offset_minutes;
+        salon_hours.weekly = weekly;
+        salon_hours.bump = ctx.bumps.salon_hours;
+
+        emit!(BusinessHoursUpdated {
+            salon: salon.key(),
+            utc_offset_minutes,
+            weekly,
+        });
+
+        Ok(())
+    }
+
+    /// Close the salon for a period, e.g. a holiday or a blocked slot (by salon owner)
+    pub fn add_closure(ctx: Context<UpdateClosures>, start: i64, end: i64) -> Result<()> {
+        require!(start < end, GlamBookError::InvalidClosure);
+
+        let now = Clock::get()?.unix_timestamp;
+        let salon_hours = &mut ctx.accounts.salon_hours;
+
+        // Past closures no longer matter; drop them to make room
+        salon_hours.closures.retain(|closure| closure.end > now);
+        require!(
+            salon_hours.closures.len() < MAX_CLOSURES,
+            GlamBookError::TooManyClosures
+        );
+        salon_hours.closures.push(Closure { start, end });
+
+        emit!(ClosureAdded {
+            salon: salon_hours.salon,
+            start,
+            end,
+        });
+
+        Ok(())
+    }
+
+    /// Reopen a closed period, identified by its start time (by salon owner)
+    pub fn remove_closure(ctx: Context<UpdateClosures>, start: i64) -> Result<()> {
+        let salon_hours = &mut ctx.accounts.salon_hours;
+
+        let index = salon_hours
+            .closures
+            .iter()
+            .position(|closure| closure.start == start)
+            .ok_or(GlamBookError::ClosureNotFound)?;
+        let closure = salon_hours.closures.remove(index);
+
+        emit!(ClosureRemoved {
+            salon: salon_hours.salon,
+            start,
+            end: closure.end,
+        });
+
+        Ok(())
+    }
+
+    /// Accept an SPL mint (e.g. USDC, EURC) for reservation payments (by admin)
+    pub fn add_payment_mint(ctx: Context<AddPaymentMint>, units_per_eur: u64) -> Result<()> {
+        require!(units_per_eur > 0, GlamBookError::InvalidConversionRate);
+
+        let config = &mut ctx.accounts.payment_mint_config;
+        config.mint = ctx.accounts.mint.key();
+        config.units_per_eur = units_per_eur;
+        config.is_active = true;
+        config.bump = ctx.bumps.payment_mint_config;
+
+        emit!(PaymentMintConfigured {
+            mint: config.mint,
+            units_per_eur,
+            is_active: true,
+        });
+
+        Ok(())
+    }
+
+    /// Update the EUR conversion rate or disable an accepted mint (by admin)
+    pub fn update_payment_mint(
+        ctx: Context<UpdatePaymentMint>,
+        units_per_eur: u64,
+        is_active: bool,
+    ) -> Result<()> {
+        require!(units_per_eur > 0, GlamBookError::InvalidConversionRate);
+
+        let config = &mut ctx.accounts.payment_mint_config;
+        config.units_per_eur = units_per_eur;
+        config.is_active = is_active;
+
+        emit!(PaymentMintConfigured {
+            mint: config.mint,
+            units_per_eur,
+            is_active,
+        });
+
+        Ok(())
+    }
+
+    /// Choose the mint new reservations are paid in; no config account means native SOL.
+    /// Prices are in base units of the mint, so switching to another mint must re-price
+    /// every service in the same call.
+    pub fn set_payment_mint(
+        ctx: Context<SetPaymentMint>,
+        prices: Vec<ServicePrice>,
+    ) -> Result<()> {
+        let payment_mint = match &ctx.accounts.payment_mint_config {
+            Some(config) => {
+                require!(config.is_active, GlamBookError::PaymentMintNotAccepted);
+                Some(config.mint)
+            }
+            None => None,
+        };
+
+        let salon = &mut ctx.accounts.salon;
+        let salon_key = salon.key();
+
+        if payment_mint == salon.payment_mint {
+            require!(prices.is_empty(), GlamBookError::InvalidServicePrices);
+        } else {
+            require!(
+                prices.len() == salon.services.len(),
+                GlamBookError::InvalidServicePrices
+            );
+            for service in salon.services.iter_mut() {
+                let price = prices
+                    .iter()
+                    .find(|price| price.service_id == service.id)
+                    .ok_or(GlamBookError::InvalidServicePrices)?;
+                service.price_lamports = price.price;
+
+                emit!(ServiceUpdated {
+                    salon: salon_key,
+                    service_id: service.id,
+                    name: service.name.clone(),
+                    price_lamports: service.price_lamports,
+                    duration_minutes: service.duration_minutes,
+                    is_active: service.is_active,
+                });
+            }
+        }
+        salon.payment_mint = payment_mint;
+
+        emit!(SalonPaymentMintUpdated {
+            salon: salon_key,
+            payment_mint,
+        });
+
+        Ok(())
+    }
+
+    /// Create the SOL/EUR price configuration used for cancellation fees (by admin)
+    pub fn initialize_price_config(
+        ctx: Context<InitializePriceConfig>,
+        lamports_per_eur: u64,
+        max_staleness_secs: i64,
+    ) -> Result<()> {
+        require!(lamports_per_eur > 0, GlamBookError::InvalidConversionRate);
+        require!(max_staleness_secs > 0, GlamBookError::InvalidStalenessWindow);
+
+        let price_config = &mut ctx.accounts.price_config;
+        price_config.lamports_per_eur = lamports_per_eur;
+        price_config.updated_at = Clock::get()?.unix_timestamp;
+        price_config.max_staleness_secs = max_staleness_secs;
+        price_config.oracle = None;
+        price_config.bump = ctx.bumps.price_config;
+
+        emit!(PriceUpdated {
+            lamports_per_eur,
+            updated_at: price_config.updated_at,
+        });
+
+        Ok(())
+    }
+
+    /// Push a new SOL/EUR price (by admin)
+    pub fn update_price(ctx: Context<UpdatePriceConfig>, lamports_per_eur: u64) -> Result<()> {
+        require!(lamports_per_eur > 0, GlamBookError::InvalidConversionRate);
+
+        let price_config = &mut ctx.accounts.price_config;
+        price_config.lamports_per_eur = lamports_per_eur;
+        price_config.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(PriceUpdated {
+            lamports_per_eur,
+            updated_at: price_config.updated_at,
+        });
+
+        Ok(())
+    }
+
+    /// Read the price from a Pyth-format SOL/EUR feed instead of the stored value (by admin)
+    pub fn set_price_oracle(
+        ctx: Context<UpdatePriceConfig>,
+        oracle: Option<Pubkey>,
+        max_staleness_secs: i64,
+    ) -> Result<()> {
+        require!(max_staleness_secs > 0, GlamBookError::InvalidStalenessWindow);
+
+        let price_config = &mut ctx.accounts.price_config;
+        price_config.oracle = oracle;
+        price_config.max_staleness_secs = max_staleness_secs;
+
+        emit!(PriceOracleUpdated {
+            oracle,
+            max_staleness_secs,
+        });
+
+        Ok(())
+    }
+
+    /// Create a new reservation with payment for one or more services (a bundle),
+    /// performed back to back from `appointment_time`
+    pub fn create_reservation(
+        ctx: Context<CreateReservation>,
+        service_ids: Vec<u8>,
+        appointment_time: i64,
+    ) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_BOOKINGS)?;
+
+        let salon = &ctx.accounts.salon;
+        let clock = Clock::get()?;
+
+        // Validate appointment time is in the future
+        require!(
+            appointment_time > clock.unix_timestamp,
+            GlamBookError::InvalidAppointmentTime
+        );
+
+        require!(
+            !service_ids.is_empty() && service_ids.len() <= MAX_LINE_ITEMS,
+            GlamBookError::InvalidLineItems
+        );
+
+        // Find each service; the bundle costs and lasts as long as its services together
+        let mut line_items = Vec::with_capacity(service_ids.len());
+        let mut amount: u64 = 0;
+        let mut duration_minutes: u16 = 0;
+        for service_id in service_ids.iter() {
+            let service = salon
+                .services
+                .iter()
+                .find(|s| s.id == *service_id && s.is_active)
+                .ok_or(GlamBookError::ServiceNotFound)?;
+
+            // Priced in base units of the salon's payment mint, or lamports for SOL
+            amount = math::add(amount, service.price_lamports)?;
+            duration_minutes = duration_minutes
+                .checked_add(service.duration_minutes)
+                .ok_or(GlamBookError::MathOverflow)?;
+            line_items.push(LineItem {
+                service_id: service.id,
+                name: service.name.clone(),
+                amount: service.price_lamports,
+                duration_minutes: service.duration_minutes,
+                refunded: false,
+            });
+        }
+
+        if salon.has_business_hours {
+            ctx.accounts
+                .salon_hours
+                .as_ref()
+                .ok_or(GlamBookError::MissingBusinessHours)?
+                .check_open(appointment_time, duration_minutes)?;
+        }
+
+        // Claim a chair for every slot the appointment overlaps
+        let schedule_day = salon.local_day(appointment_time)?;
+        let schedule = &mut ctx.accounts.schedule;
+        if schedule.resource == Pubkey::default() {
+            schedule.resource = salon.key();
+            schedule.day = schedule_day;
+            schedule.utc_offset_minutes = salon.utc_offset_minutes;
+            schedule.bump = ctx.bumps.schedule;
+        }
+        schedule.reserve(appointment_time, duration_minutes, salon.chairs)?;
+
+        // A booked stylist also needs a free slot in their own schedule
+        let staff_assignment = match &ctx.accounts.staff {
+            Some(staff) => {
+                require!(staff.is_active, GlamBookError::StaffInactive);
+                require!(
+                    service_ids.iter().all(|id| staff.service_ids.contains(id)),
+                    GlamBookError::StaffServiceNotOffered
+                );
+
+                let staff_schedule = ctx
+                    .accounts
+                    .staff_schedule
+                    .as_mut()
+                    .ok_or(GlamBookError::InvalidSchedule)?;
+                if staff_schedule.resource == Pubkey::default() {
+                    staff_schedule.resource = staff.key();
+                    staff_schedule.day = schedule_day;
+                    staff_schedule.utc_offset_minutes = salon.utc_offset_minutes;
+                    staff_schedule.bump = ctx.bumps.staff_schedule;
+                }
+                staff_schedule.reserve(appointment_time, duration_minutes, staff.capacity)?;
+
+                Some(StaffAssignment {
+                    staff: staff.key(),
+                    wallet: staff.wallet,
+                    commission_split_bps: staff.commission_split_bps,
+                })
+            }
+            None => {
+                require!(
+                    ctx.accounts.staff_schedule.is_none(),
+                    GlamBookError::InvalidSchedule
+                );
+                None
+            }
+        };
+
+        // Only the deposit is escrowed; the balance is due at the venue or via pay_balance
+        let deposit = math::bps(amount, salon.deposit_bps)?;
+        let balance_due = math::sub(amount, deposit)?;
+
+        match salon.payment_mint {
+            Some(mint) => {
+                // Transfer payment from client token account to the reservation's escrow
+                let payment_mint = ctx
+                    .accounts
+                    .payment_mint
+                    .as_ref()
+                    .ok_or(GlamBookError::MissingTokenAccounts)?;
+                require!(payment_mint.key() == mint, GlamBookError::InvalidPaymentMint);
+
+                let client_token_account = ctx
+                    .accounts
+                    .client_token_account
+                    .as_ref()
+                    .ok_or(GlamBookError::MissingTokenAccounts)?;
+                let escrow_token_account = ctx
+                    .accounts
+                    .escrow_token_account
+                    .as_ref()
+                    .ok_or(GlamBookError::MissingTokenAccounts)?;
+                let token_program = ctx
+                    .accounts
+                    .token_program
+                    .as_ref()
+                    .ok_or(GlamBookError::MissingTokenAccounts)?;
+
+                let cpi_context = CpiContext::new(
+                    token_program.to_account_info(),
+                    token::Transfer {
+                        from: client_token_account.to_account_info(),
+                        to: escrow_token_account.to_account_info(),
+                        authority: ctx.accounts.client.to_account_info(),
+                    },
+                );
+                token::transfer(cpi_context, deposit)?;
+            }
+            None => {
+                // Transfer payment from client to escrow (reservation PDA)
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.client.to_account_info(),
+                        to: ctx.accounts.reservation.to_account_info(),
+                    },
+                );
+                system_program::transfer(cpi_context, deposit)?;
+            }
+        }
+
+        // Initialize reservation account
+        let reservation = &mut ctx.accounts.reservation;
+        reservation.client = ctx.accounts.client.key();
+        reservation.salon = salon.key();
+        reservation.salon_owner = salon.owner;
+        reservation.line_items = line_items;
+        reservation.amount = deposit;
+        reservation.balance_due = balance_due;
+        reservation.payment_mint = salon.payment_mint;
+        reservation.cancellation_policy = salon.cancellation_policy.clone();
+        reservation.appointment_time = appointment_time;
+        reservation.duration_minutes = duration_minutes;
+        reservation.created_at = clock.unix_timestamp;
+        reservation.status = ReservationStatus::Confirmed;
+        reservation.staff = staff_assignment;
+        reservation.schedule_day = schedule_day;
+        reservation.bump = ctx.bumps.reservation;
+
+        // Update platform stats
+        let platform = &mut ctx.accounts.platform;
+        platform.total_reservations = math::add(platform.total_reservations, 1)?;
+        platform.total_volume = math::add(platform.total_volume, deposit)?;
+
+        // Update salon stats
+        let salon_mut = &mut ctx.accounts.salon;
+        salon_mut.reservation_count = math::add(salon_mut.reservation_count, 1)?;
+
+        emit!(ReservationCreated {
             reservation: reservation.key(),
             client: reservation.client,
-            salon_payment,
+            salon: reservation.salon,
+            service_ids,
+            amount: deposit,
+            balance_due,
+            payment_mint: reservation.payment_mint,
+            appointment_time,
+            staff: reservation.staff.as_ref().map(|assignment| assignment.staff),
+        });
+
+        Ok(())
+    }
+
+    /// Pay the rest of a deposit booking into escrow before completion (by client)
+    pub fn pay_balance(ctx: Context<PayBalance>) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_BOOKINGS)?;
+
+        let reservation = &ctx.accounts.reservation;
+        require!(
+            reservation.status == ReservationStatus::Confirmed,
+            GlamBookError::InvalidReservationStatus
+        );
+        let balance = reservation.balance_due;
+        require!(balance > 0, GlamBookError::NoBalanceDue);
+
+        match reservation.payment_mint {
+            Some(_) => {
+                // Transfer the balance from client token account to the reservation's escrow
+                let client_token_account = ctx
+                    .accounts
+                    .client_token_account
+                    .as_ref()
+                    .ok_or(GlamBookError::MissingTokenAccounts)?;
+                let escrow_token_account = ctx
+                    .accounts
+                    .escrow_token_account
+                    .as_ref()
+                    .ok_or(GlamBookError::MissingTokenAccounts)?;
+                let token_program = ctx
+                    .accounts
+                    .token_program
+                    .as_ref()
+                    .ok_or(GlamBookError::MissingTokenAccounts)?;
+
+                let cpi_context = CpiContext::new(
+                    token_program.to_account_info(),
+                    token::Transfer {
+                        from: client_token_account.to_account_info(),
+                        to: escrow_token_account.to_account_info(),
+                        authority: ctx.accounts.client.to_account_info(),
+                    },
+                );
+                token::transfer(cpi_context, balance)?;
+            }
+            None => {
+                // Transfer the balance from client to escrow (reservation PDA)
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.client.to_account_info(),
+                        to: ctx.accounts.reservation.to_account_info(),
+                    },
+                );
+                system_program::transfer(cpi_context, balance)?;
+            }
+        }
+
+        let reservation = &mut ctx.accounts.reservation;
+        reservation.amount = math::add(reservation.amount, balance)?;
+        reservation.balance_due = 0;
+
+        let platform = &mut ctx.accounts.platform;
+        platform.total_volume = math::add(platform.total_volume, balance)?;
+
+        emit!(BalancePaid {
+            reservation: reservation.key(),
+            client: reservation.client,
+            amount: balance,
+            total_held: reservation.amount,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a reservation and process refund
+    pub fn cancel_reservation(ctx: Context<CancelReservation>) -> Result<()> {
+        let reservation = &mut ctx.accounts.reservation;
+        let clock = Clock::get()?;
+
+        // Only client can cancel their own reservation
+        require!(
+            ctx.accounts.client.key() == reservation.client,
+            GlamBookError::UnauthorizedCancellation
+        );
+
+        // Can only cancel confirmed reservations
+        require!(
+            reservation.status == ReservationStatus::Confirmed,
+            GlamBookError::InvalidReservationStatus
+        );
+
+        let time_until_appointment =
+            math::sub_secs(reservation.appointment_time, clock.unix_timestamp)?;
+        let (refund_bps, fee_eur_cents) = reservation
+            .cancellation_policy
+            .terms(time_until_appointment);
+
+        // Salon fees are set in EUR and converted into the reservation's payment unit.
+        // The price is only needed (and checked for staleness) when a fee applies, so
+        // full refunds never depend on the price being fresh.
+        let salon_fee = if fee_eur_cents == 0 || refund_bps as u64 >= BPS_DENOMINATOR {
+            0
+        } else {
+            let units_per_eur = match reservation.payment_mint {
+                Some(mint) => {
+                    let config = ctx
+                        .accounts
+                        .payment_mint_config
+                        .as_ref()
+                        .ok_or(GlamBookError::MissingTokenAccounts)?;
+                    require!(config.mint == mint, GlamBookError::InvalidPaymentMint);
+                    config.units_per_eur
+                }
+                None => sol_lamports_per_eur(
+                    ctx.accounts
+                        .price_config
+                        .as_ref()
+                        .ok_or(GlamBookError::MissingPriceConfig)?,
+                    ctx.accounts.price_feed.as_ref(),
+                    clock.unix_timestamp,
+                )?,
+            };
+            math::mul_div(fee_eur_cents, units_per_eur, 100)?
+        };
+
+        // Calculate refund based on the policy snapshotted at booking
+        let (client_refund, salon_fee, app_commission) =
+            calculate_refund(
+                reservation.amount,
+                refund_bps,
+                salon_fee,
+                ctx.accounts.platform.commission_bps,
+            )?;
+        math::require_split(reservation.amount, &[client_refund, salon_fee, app_commission])?;
+
+        // Update reservation status and free the booked slots
+        reservation.status = ReservationStatus::Cancelled;
+        reservation.cancelled_at = Some(clock.unix_timestamp);
+        ctx.accounts.schedule.release(reservation)?;
+        if reservation.staff.is_some() {
+            ctx.accounts
+                .staff_schedule
+                .as_mut()
+                .ok_or(GlamBookError::InvalidSchedule)?
+                .release(reservation)?;
+        }
+
+        let accounts = &ctx.accounts;
+        let escrow = accounts.escrow_token_account.as_ref();
+        let token_program = accounts.token_program.as_ref();
+
+        // Transfer refund to client
+        pay_from_escrow(
+            &accounts.reservation,
+            escrow,
+            token_program,
+            &accounts.client.to_account_info(),
+            accounts.client_token_account.as_ref(),
+            client_refund,
+        )?;
+
+        // Transfer fee to salon payout wallet
+        pay_from_escrow(
+            &accounts.reservation,
+            escrow,
+            token_program,
+            &accounts.payout_wallet,
+            accounts.salon_token_account.as_ref(),
+            salon_fee,
+        )?;
+
+        // Transfer commission to platform treasury
+        pay_from_escrow(
+            &accounts.reservation,
+            escrow,
+            token_program,
+            &accounts.treasury,
+            accounts.treasury_token_account.as_ref(),
+            app_commission,
+        )?;
+
+        emit!(ReservationCancelled {
+            reservation: accounts.reservation.key(),
+            client: accounts.reservation.client,
+            refund_amount: client_refund,
+            salon_fee,
             app_commission,
+            time_until_appointment,
+        });
+
+        emit!(RefundProcessed {
+            reservation: accounts.reservation.key(),
+            client: accounts.reservation.client,
+            amount: client_refund,
         });
 
+        if accounts.platform.auto_close_reservations {
+            close_reservation_accounts(
+                &accounts.reservation,
+                escrow,
+                token_program,
+                &accounts.client.to_account_info(),
+            )?;
+        }
+
         Ok(())
     }
-}
 
-/// Calculate refund amounts based on cancellation policy
-fn calculate_refund(amount: u64, time_until_appointment: i64) -> Result<(u64, u64, u64)> {
-    let (client_percentage, salon_fee_eur) = if time_until_appointment > HOURS_48 {
-        // > 48 hours: 100% refund
-        (100u64, 0u64)
-    } else if time_until_appointment > HOURS_24 {
-        // 24-48 hours: 80% refund, €2 salon fee
-        (80u64, 2u64)
-    } else if time_until_appointment > 0 {
-        // < 24 hours: 50% refund, €5 salon fee
-        (50u64, 5u64)
-    } else {
-        // No-show (past appointment time): 0% refund, €10 salon fee
-        (0u64, 10u64)
-    };
+    /// Move a reservation to a new time (by client).
+    /// The escrow moves into a new reservation PDA; the old one is left as Rescheduled.
+    pub fn reschedule_reservation(
+        ctx: Context<RescheduleReservation>,
+        new_appointment_time: i64,
+    ) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_BOOKINGS)?;
+
+        let reservation = &mut ctx.accounts.reservation;
+        let salon = &ctx.accounts.salon;
+        let clock = Clock::get()?;
+
+        require!(
+            reservation.status == ReservationStatus::Confirmed,
+            GlamBookError::InvalidReservationStatus
+        );
+        require!(
+            new_appointment_time > clock.unix_timestamp,
+            GlamBookError::InvalidAppointmentTime
+        );
+
+        let policy = &salon.reschedule_policy;
+        let time_until_appointment =
+            math::sub_secs(reservation.appointment_time, clock.unix_timestamp)?;
+        require!(
+            time_until_appointment > policy.min_notice_secs,
+            GlamBookError::RescheduleWindowClosed
+        );
+        require!(
+            !policy.requires_salon_approval || ctx.accounts.salon_owner.is_signer,
+            GlamBookError::SalonApprovalRequired
+        );
+
+        if salon.has_business_hours {
+            ctx.accounts
+                .salon_hours
+                .as_ref()
+                .ok_or(GlamBookError::MissingBusinessHours)?
+                .check_open(new_appointment_time, reservation.duration_minutes)?;
+        }
+
+        // Free the old slots and claim the new ones, for the salon and the booked stylist
+        move_booking(
+            &mut ctx.accounts.schedule,
+            ctx.accounts.new_schedule.as_deref_mut(),
+            ctx.bumps.new_schedule,
+            reservation,
+            new_appointment_time,
+            salon.utc_offset_minutes,
+            salon.chairs,
+        )?;
+        if reservation.staff.is_some() {
+            let staff = ctx.accounts.staff.as_ref().ok_or(GlamBookError::InvalidStaff)?;
+            require!(staff.is_active, GlamBookError::StaffInactive);
+            move_booking(
+                ctx.accounts
+                    .staff_schedule
+                    .as_mut()
+                    .ok_or(GlamBookError::InvalidSchedule)?,
+                ctx.accounts.new_staff_schedule.as_deref_mut(),
+                ctx.bumps.new_staff_schedule,
+                reservation,
+                new_appointment_time,
+                salon.utc_offset_minutes,
+                staff.capacity,
+            )?;
+        }
+
+        // The fee is kept by the salon now; the rest of the escrow follows the booking
+        let fee = math::bps(reservation.amount, policy.fee_bps)?;
+        let app_commission = math::bps(fee, ctx.accounts.platform.commission_bps)?;
+        let salon_fee = math::sub(fee, app_commission)?;
+        let moved_amount = math::sub(reservation.amount, fee)?;
+        math::require_split(reservation.amount, &[moved_amount, salon_fee, app_commission])?;
+
+        reservation.status = ReservationStatus::Rescheduled;
+
+        let new_reservation = &mut ctx.accounts.new_reservation;
+        new_reservation.client = reservation.client;
+        new_reservation.salon = reservation.salon;
+        new_reservation.salon_owner = reservation.salon_owner;
+        new_reservation.line_items = reservation.line_items.clone();
+        new_reservation.amount = moved_amount;
+        new_reservation.balance_due = reservation.balance_due;
+        new_reservation.payment_mint = reservation.payment_mint;
+        new_reservation.cancellation_policy = reservation.cancellation_policy.clone();
+        new_reservation.appointment_time = new_appointment_time;
+        new_reservation.duration_minutes = reservation.duration_minutes;
+        new_reservation.created_at = clock.unix_timestamp;
+        new_reservation.status = ReservationStatus::Confirmed;
+        new_reservation.staff = reservation.staff.clone();
+        new_reservation.schedule_day = salon.local_day(new_appointment_time)?;
+        new_reservation.bump = ctx.bumps.new_reservation;
+
+        let accounts = &ctx.accounts;
+        let escrow = accounts.escrow_token_account.as_ref();
+        let token_program = accounts.token_program.as_ref();
+
+        // Move the escrow into the new reservation (its PDA or escrow token account)
+        pay_from_escrow(
+            &accounts.reservation,
+            escrow,
+            token_program,
+            &accounts.new_reservation.to_account_info(),
+            accounts.new_escrow_token_account.as_ref(),
+            moved_amount,
+        )?;
+
+        // Transfer reschedule fee to salon payout wallet
+        pay_from_escrow(
+            &accounts.reservation,
+            escrow,
+            token_program,
+            &accounts.payout_wallet,
+            accounts.salon_token_account.as_ref(),
+            salon_fee,
+        )?;
+
+        // Transfer commission to platform treasury
+        pay_from_escrow(
+            &accounts.reservation,
+            escrow,
+            token_program,
+            &accounts.treasury,
+            accounts.treasury_token_account.as_ref(),
+            app_commission,
+        )?;
+
+        emit!(ReservationRescheduled {
+            old_reservation: accounts.reservation.key(),
+            new_reservation: accounts.new_reservation.key(),
+            client: accounts.reservation.client,
+            old_appointment_time: accounts.reservation.appointment_time,
+            new_appointment_time,
+            salon_fee,
+            app_commission,
+        });
+
+        // The old reservation is settled once its escrow has moved on
+        if accounts.platform.auto_close_reservations {
+            close_reservation_accounts(
+                &accounts.reservation,
+                escrow,
+                token_program,
+                &accounts.client.to_account_info(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a reservation on the salon's side (by salon owner).
+    /// The client gets everything back, plus optional compensation paid from the bond.
+    pub fn salon_cancel_reservation(
+        ctx: Context<SalonCancelReservation>,
+        compensation: u64,
+    ) -> Result<()> {
+        let reservation = &mut ctx.accounts.reservation;
+        let clock = Clock::get()?;
+
+        require!(
+            reservation.status == ReservationStatus::Confirmed,
+            GlamBookError::InvalidReservationStatus
+        );
+
+        let salon = &mut ctx.accounts.salon;
+        require!(
+            compensation <= salon.bond_lamports,
+            GlamBookError::InsufficientBond
+        );
+
+        let refund_amount = reservation.amount;
+        reservation.status = ReservationStatus::CancelledBySalon;
+        reservation.cancelled_at = Some(clock.unix_timestamp);
+        ctx.accounts.schedule.release(reservation)?;
+        if reservation.staff.is_some() {
+            ctx.accounts
+                .staff_schedule
+                .as_mut()
+                .ok_or(GlamBookError::InvalidSchedule)?
+                .release(reservation)?;
+        }
+
+        salon.salon_cancellations = math::add(salon.salon_cancellations, 1)?;
+        salon.bond_lamports = math::sub(salon.bond_lamports, compensation)?;
+
+        // Compensation is always SOL, taken from the bond held by the salon PDA
+        transfer_out(&salon.to_account_info(), &ctx.accounts.client, Some(compensation))?;
+
+        let accounts = &ctx.accounts;
+
+        // Full refund to client
+        pay_from_escrow(
+            &accounts.reservation,
+            accounts.escrow_token_account.as_ref(),
+            accounts.token_program.as_ref(),
+            &accounts.client,
+            accounts.client_token_account.as_ref(),
+            refund_amount,
+        )?;
+
+        emit!(ReservationCancelledBySalon {
+            reservation: accounts.reservation.key(),
+            salon: accounts.salon.key(),
+            client: accounts.reservation.client,
+            refund_amount,
+            compensation,
+        });
+
+        emit!(RefundProcessed {
+            reservation: accounts.reservation.key(),
+            client: accounts.reservation.client,
+            amount: refund_amount,
+        });
+
+        if accounts.platform.auto_close_reservations {
+            close_reservation_accounts(
+                &accounts.reservation,
+                accounts.escrow_token_account.as_ref(),
+                accounts.token_program.as_ref(),
+                &accounts.client,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Settle a reservation nobody closed within the settlement window, or release
+    /// a payout held for the dispute window (by anyone). Settling holds the payout
+    /// like a salon-driven completion; the caller releasing it earns a share of the
+    /// platform commission.
+    pub fn settle_expired_reservation(ctx: Context<SettleExpiredReservation>) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_PAYOUTS)?;
+
+        let reservation = &mut ctx.accounts.reservation;
+        let platform = &ctx.accounts.platform;
+        let clock = Clock::get()?;
+
+        match reservation.status {
+            // Never closed by the salon: finalize per platform policy
+            ReservationStatus::Confirmed => {
+                require!(
+                    clock.unix_timestamp
+                        >= math::add_secs(
+                            reservation.appointment_time,
+                            platform.settlement_window_secs
+                        )?,
+                    GlamBookError::SettlementWindowOpen
+                );
+                // Like mark_no_show, a no-show leaves completed_at unset
+                match platform.expired_settlement {
+                    ExpiredSettlement::Complete => {
+                        reservation.status = ReservationStatus::Completed;
+                        reservation.completed_at = Some(clock.unix_timestamp);
+                    }
+                    ExpiredSettlement::NoShow => reservation.status = ReservationStatus::NoShow,
+                }
+
+                // Hold the payout while the client can still dispute, like a salon-driven
+                // completion or no-show; a later call releases it
+                if platform.dispute_window_secs > 0 {
+                    let held_until =
+                        math::add_secs(clock.unix_timestamp, platform.dispute_window_secs)?;
+                    reservation.payout_held_until = Some(held_until);
+
+                    let amount = reservation.amount;
+                    let app_commission = math::bps(amount, platform.commission_bps)?;
+                    let salon_share = math::sub(amount, app_commission)?;
+                    if reservation.status == ReservationStatus::Completed {
+                        let staff_payment = reservation.staff_payment(salon_share)?;
+                        emit!(ReservationCompleted {
+                            reservation: reservation.key(),
+                            salon_payment: math::sub(salon_share, staff_payment)?,
+                            staff_payment,
+                            app_commission,
+                            payout_held_until: Some(held_until),
+                        });
+                    } else {
+                        emit!(ReservationNoShow {
+                            reservation: reservation.key(),
+                            client: reservation.client,
+                            salon_payment: salon_share,
+                            app_commission,
+                            payout_held_until: Some(held_until),
+                        });
+                    }
+                    return Ok(());
+                }
+            }
+            // Completed or no-show with the payout held for the dispute window
+            ReservationStatus::Completed | ReservationStatus::NoShow => {
+                let held_until = reservation
+                    .payout_held_until
+                    .ok_or(GlamBookError::InvalidReservationStatus)?;
+                require!(
+                    clock.unix_timestamp >= held_until,
+                    GlamBookError::DisputeWindowOpen
+                );
+            }
+            _ => return err!(GlamBookError::InvalidReservationStatus),
+        }
+        reservation.payout_held_until = None;
+
+        // Same split as a completion; the crank reward comes out of the commission
+        let amount = reservation.amount;
+        let commission = math::bps(amount, platform.commission_bps)?;
+        let crank_reward = math::bps(commission, platform.crank_reward_bps)?;
+        let app_commission = math::sub(commission, crank_reward)?;
+        let salon_share = math::sub(amount, commission)?;
+
+        let accounts = &ctx.accounts;
+        let (salon_payment, staff_payment) = pay_out_settlement(
+            &settlement_accounts!(accounts),
+            salon_share,
+            app_commission,
+            amount,
+            &[crank_reward],
+        )?;
+
+        // Reward the caller
+        pay_from_escrow(
+            &accounts.reservation,
+            accounts.escrow_token_account.as_ref(),
+            accounts.token_program.as_ref(),
+            &accounts.cranker.to_account_info(),
+            accounts.cranker_token_account.as_ref(),
+            crank_reward,
+        )?;
+
+        let salon = &mut ctx.accounts.salon;
+        salon.total_earnings = math::add(salon.total_earnings, salon_payment)?;
+
+        emit!(ReservationSettled {
+            reservation: ctx.accounts.reservation.key(),
+            status: ctx.accounts.reservation.status.clone(),
+            salon_payment,
+            staff_payment,
+            app_commission,
+            cranker: ctx.accounts.cranker.key(),
+            crank_reward,
+        });
+
+        let accounts = &ctx.accounts;
+        if accounts.platform.auto_close_reservations {
+            close_reservation_accounts(
+                &accounts.reservation,
+                accounts.escrow_token_account.as_ref(),
+                accounts.token_program.as_ref(),
+                &accounts.client,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Mark reservation as completed (by salon owner or a delegate)
+    pub fn complete_reservation(ctx: Context<CompleteReservation>) -> Result<()> {
+        let amount = ctx.accounts.reservation.amount;
+        settle_completion(ctx, amount)
+    }
+
+    /// Complete a reservation for part of its price (by salon owner or manager).
+    /// The rest is refunded to the client; commission is only taken on the salon's part.
+    pub fn complete_reservation_partial(
+        ctx: Context<CompleteReservation>,
+        amount_to_salon: u64,
+    ) -> Result<()> {
+        let accounts = &ctx.accounts;
+        require!(
+            matches!(
+                accounts.salon.role_of(&accounts.operator.key()),
+                Some(SalonRole::Owner | SalonRole::Manager)
+            ),
+            GlamBookError::UnauthorizedPartialCompletion
+        );
+        require!(amount_to_salon > 0, GlamBookError::InvalidPartialAmount);
+
+        settle_completion(ctx, amount_to_salon)
+    }
+
+    /// Client confirms the service was delivered, releasing the payout right away.
+    /// Completes the reservation if the salon has not yet, or ends the review
+    /// (dispute) window of a held completion payout.
+    pub fn confirm_service(ctx: Context<ConfirmService>) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_PAYOUTS)?;
+
+        let reservation = &mut ctx.accounts.reservation;
+        let clock = Clock::get()?;
+
+        match reservation.status {
+            ReservationStatus::Confirmed => {
+                require!(
+                    clock.unix_timestamp >= reservation.appointment_time,
+                    GlamBookError::AppointmentNotYetDue
+                );
+                reservation.status = ReservationStatus::Completed;
+                reservation.completed_at = Some(clock.unix_timestamp);
+            }
+            // Only a payout still held for review can be released
+            ReservationStatus::Completed => {
+                require!(
+                    reservation.payout_held_until.is_some(),
+                    GlamBookError::InvalidReservationStatus
+                );
+            }
+            _ => return err!(GlamBookError::InvalidReservationStatus),
+        }
+        reservation.payout_held_until = None;
+
+        // Same split as a completion
+        let amount = reservation.amount;
+        let app_commission = math::bps(amount, ctx.accounts.platform.commission_bps)?;
+        let salon_share = math::sub(amount, app_commission)?;
+
+        let accounts = &ctx.accounts;
+        let (salon_payment, staff_payment) = pay_out_settlement(
+            &settlement_accounts!(accounts),
+            salon_share,
+            app_commission,
+            amount,
+            &[],
+        )?;
+
+        let salon = &mut ctx.accounts.salon;
+        salon.total_earnings = math::add(salon.total_earnings, salon_payment)?;
+
+        emit!(ServiceConfirmed {
+            reservation: ctx.accounts.reservation.key(),
+            client: ctx.accounts.client.key(),
+            salon_payment,
+            staff_payment,
+            app_commission,
+        });
+
+        let accounts = &ctx.accounts;
+        if accounts.platform.auto_close_reservations {
+            close_reservation_accounts(
+                &accounts.reservation,
+                accounts.escrow_token_account.as_ref(),
+                accounts.token_program.as_ref(),
+                &accounts.client.to_account_info(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Tip the stylist (or the salon without one) for a completed reservation.
+    /// Paid in SOL straight from the client; `platform.tip_fee_bps` goes to the treasury.
+    pub fn tip(ctx: Context<Tip>, amount: u64) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_PAYOUTS)?;
+        require!(amount > 0, GlamBookError::InvalidTipAmount);
+        require!(
+            ctx.accounts.reservation.status == ReservationStatus::Completed,
+            GlamBookError::InvalidReservationStatus
+        );
+
+        let tip_fee = math::bps(amount, ctx.accounts.platform.tip_fee_bps)?;
+        let recipient_amount = math::sub(amount, tip_fee)?;
+        math::require_split(amount, &[recipient_amount, tip_fee])?;
+
+        let reservation = &mut ctx.accounts.reservation;
+        reservation.tips = math::add(reservation.tips, amount)?;
+
+        let recipient = match ctx.accounts.reservation.staff.as_ref() {
+            Some(_) => ctx
+                .accounts
+                .staff_wallet
+                .as_ref()
+                .ok_or(GlamBookError::InvalidStaff)?
+                .to_account_info(),
+            None => ctx.accounts.payout_wallet.to_account_info(),
+        };
+
+        // The runtime rejects lamports that leave an account short of rent exemption,
+        // e.g. a small tip to a stylist's new wallet
+        let rent = Rent::get()?;
+        let treasury = ctx.accounts.treasury.to_account_info();
+        for (wallet, credit) in [(&recipient, recipient_amount), (&treasury, tip_fee)] {
+            require!(
+                credit == 0
+                    || rent.is_exempt(math::add(wallet.lamports(), credit)?, wallet.data_len()),
+                GlamBookError::TipBelowRentExemption
+            );
+        }
+
+        // Transfer the tip to the stylist or salon
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.client.to_account_info(),
+                to: recipient.clone(),
+            },
+        );
+        system_program::transfer(cpi_context, recipient_amount)?;
+
+        // Transfer the tip fee to platform treasury
+        if tip_fee > 0 {
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.client.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, tip_fee)?;
+        }
+
+        let reservation = &ctx.accounts.reservation;
+        emit!(TipPaid {
+            reservation: reservation.key(),
+            client: reservation.client,
+            recipient: recipient.key(),
+            amount: recipient_amount,
+            tip_fee,
+        });
+
+        Ok(())
+    }
+
+    /// Mark reservation as no-show (by salon owner or a delegate, after appointment time)
+    pub fn mark_no_show(ctx: Context<MarkNoShow>) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_PAYOUTS)?;
+
+        let reservation = &mut ctx.accounts.reservation;
+        let clock = Clock::get()?;
+
+        // Must be confirmed status
+        require!(
+            reservation.status == ReservationStatus::Confirmed,
+            GlamBookError::InvalidReservationStatus
+        );
+
+        // Can only mark no-show after appointment time + grace period
+        require!(
+            clock.unix_timestamp
+                >= math::add_secs(
+                    reservation.appointment_time,
+                    ctx.accounts.platform.no_show_grace_secs
+                )?,
+            GlamBookError::TooEarlyForNoShow
+        );
+
+        let amount = reservation.amount;
+        // No-show: 0% refund, 100% to salon minus commission
+        let app_commission = math::bps(amount, ctx.accounts.platform.commission_bps)?;
+        let salon_payment = math::sub(amount, app_commission)?;
+        math::require_split(amount, &[salon_payment, app_commission])?;
+
+        reservation.status = ReservationStatus::NoShow;
+
+        // Hold the payout while the client can still dispute
+        let dispute_window_secs = ctx.accounts.platform.dispute_window_secs;
+        if dispute_window_secs > 0 {
+            reservation.payout_held_until =
+                Some(math::add_secs(clock.unix_timestamp, dispute_window_secs)?);
+        } else {
+            let accounts = &ctx.accounts;
+            let escrow = accounts.escrow_token_account.as_ref();
+            let token_program = accounts.token_program.as_ref();
+
+            // Transfer payment to salon payout wallet
+            pay_from_escrow(
+                &accounts.reservation,
+                escrow,
+                token_program,
+                &accounts.payout_wallet,
+                accounts.salon_token_account.as_ref(),
+                salon_payment,
+            )?;
+
+            // Transfer commission to platform treasury
+            pay_from_escrow(
+                &accounts.reservation,
+                escrow,
+                token_program,
+                &accounts.treasury,
+                accounts.treasury_token_account.as_ref(),
+                app_commission,
+            )?;
+
+            // Update salon earnings
+            let salon = &mut ctx.accounts.salon;
+            salon.total_earnings = math::add(salon.total_earnings, salon_payment)?;
+        }
+
+        emit!(ReservationNoShow {
+            reservation: ctx.accounts.reservation.key(),
+            client: ctx.accounts.reservation.client,
+            salon_payment,
+            app_commission,
+            payout_held_until: ctx.accounts.reservation.payout_held_until,
+        });
+
+        // Without a dispute window the payout is final, so the reservation is settled
+        let accounts = &ctx.accounts;
+        if accounts.platform.auto_close_reservations
+            && accounts.reservation.payout_held_until.is_none()
+        {
+            let client = accounts.client.as_ref().ok_or(GlamBookError::InvalidClient)?;
+            close_reservation_accounts(
+                &accounts.reservation,
+                accounts.escrow_token_account.as_ref(),
+                accounts.token_program.as_ref(),
+                &client.to_account_info(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Refund one service of a confirmed bundle in full, e.g. when it cannot be
+    /// performed (by salon owner or manager). The rest of the bundle stays booked;
+    /// the slots only the refunded service ran through are freed.
+    pub fn refund_line_item(ctx: Context<RefundLineItem>, index: u8) -> Result<()> {
+        let reservation = &mut ctx.accounts.reservation;
+
+        require!(
+            reservation.status == ReservationStatus::Confirmed,
+            GlamBookError::InvalidReservationStatus
+        );
+
+        let remaining = reservation
+            .line_items
+            .iter()
+            .filter(|item| !item.refunded)
+            .count();
+        let remaining_total = reservation
+            .line_items
+            .iter()
+            .filter(|item| !item.refunded)
+            .try_fold(0u64, |total, item| math::add(total, item.amount))?;
+        let item = reservation
+            .line_items
+            .get(index as usize)
+            .ok_or(GlamBookError::LineItemNotFound)?;
+        require!(!item.refunded, GlamBookError::LineItemNotFound);
+        // Refunding the whole booking is a salon cancellation
+        require!(remaining > 1, GlamBookError::InvalidLineItems);
+
+        // Free the chair and stylist for the item's time before it stops counting
+        ctx.accounts
+            .schedule
+            .release_line_item(reservation, index as usize)?;
+        if reservation.staff.is_some() {
+            ctx.accounts
+                .staff_schedule
+                .as_mut()
+                .ok_or(GlamBookError::InvalidSchedule)?
+                .release_line_item(reservation, index as usize)?;
+        }
+
+        let item = &mut reservation.line_items[index as usize];
+        item.refunded = true;
+        let service_id = item.service_id;
+        let item_amount = item.amount;
+
+        // The item is worth its share of what is still booked, which a reschedule fee
+        // may have shrunk below the listed prices
+        let booked = math::add(reservation.amount, reservation.balance_due)?;
+        let item_value = math::mul_div(item_amount, booked, remaining_total)?;
+
+        // An unpaid balance is waived first; only the rest comes out of escrow
+        let waived = item_value.min(reservation.balance_due);
+        let refund_amount = math::sub(item_value, waived)?;
+        let kept_amount = math::sub(reservation.amount, refund_amount)?;
+        math::require_split(reservation.amount, &[refund_amount, kept_amount])?;
+        reservation.balance_due = math::sub(reservation.balance_due, waived)?;
+        reservation.amount = kept_amount;
+
+        let accounts = &ctx.accounts;
+
+        // Transfer the line item back to the client
+        pay_from_escrow(
+            &accounts.reservation,
+            accounts.escrow_token_account.as_ref(),
+            accounts.token_program.as_ref(),
+            &accounts.client,
+            accounts.client_token_account.as_ref(),
+            refund_amount,
+        )?;
+
+        emit!(LineItemRefunded {
+            reservation: accounts.reservation.key(),
+            client: accounts.reservation.client,
+            index,
+            service_id,
+            refund_amount,
+            refunded_by: accounts.operator.key(),
+        });
+
+        emit!(RefundProcessed {
+            reservation: accounts.reservation.key(),
+            client: accounts.reservation.client,
+            amount: refund_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Dispute a completion or no-show while its payout is held (by client)
+    pub fn open_dispute(ctx: Context<OpenDispute>, evidence_hash: [u8; 32]) -> Result<()> {
+        let reservation = &mut ctx.accounts.reservation;
+        let clock = Clock::get()?;
+
+        require!(
+            reservation.status == ReservationStatus::Completed
+                || reservation.status == ReservationStatus::NoShow,
+            GlamBookError::InvalidReservationStatus
+        );
+        let held_until = reservation
+            .payout_held_until
+            .ok_or(GlamBookError::DisputeWindowClosed)?;
+        require!(
+            clock.unix_timestamp < held_until,
+            GlamBookError::DisputeWindowClosed
+        );
+
+        reservation.status = ReservationStatus::Disputed;
+        reservation.evidence_hash = Some(evidence_hash);
+
+        emit!(DisputeOpened {
+            reservation: reservation.key(),
+            client: reservation.client,
+            evidence_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Split a disputed reservation between client and salon (by arbiter).
+    /// Commission is only taken on the salon's share.
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, client_bps: u16) -> Result<()> {
+        ctx.accounts.platform.require_not_paused(PAUSE_PAYOUTS)?;
+        require!(
+            client_bps as u64 <= BPS_DENOMINATOR,
+            GlamBookError::InvalidDisputeSplit
+        );
+
+        let reservation = &mut ctx.accounts.reservation;
+        require!(
+            reservation.status == ReservationStatus::Disputed,
+            GlamBookError::InvalidReservationStatus
+        );
+
+        // A disputed completion still splits the salon's part with the stylist
+        let amount = reservation.amount;
+        let client_refund = math::bps(amount, client_bps)?;
+        let salon_share = math::sub(amount, client_refund)?;
+        let app_commission = math::bps(salon_share, ctx.accounts.platform.commission_bps)?;
+        let salon_net = math::sub(salon_share, app_commission)?;
+
+        reservation.status = ReservationStatus::DisputeResolved;
+        reservation.payout_held_until = None;
+
+        let accounts = &ctx.accounts;
+        let (salon_payment, staff_payment) = pay_out_settlement(
+            &settlement_accounts!(accounts),
+            salon_net,
+            app_commission,
+            amount,
+            &[client_refund],
+        )?;
+
+        // Transfer the client's share
+        pay_from_escrow(
+            &accounts.reservation,
+            accounts.escrow_token_account.as_ref(),
+            accounts.token_program.as_ref(),
+            &accounts.client,
+            accounts.client_token_account.as_ref(),
+            client_refund,
+        )?;
+
+        let salon = &mut ctx.accounts.salon;
+        salon.total_earnings = math::add(salon.total_earnings, salon_payment)?;
+
+        emit!(DisputeResolved {
+            reservation: ctx.accounts.reservation.key(),
+            arbiter: ctx.accounts.arbiter.key(),
+            client_bps,
+            client_refund,
+            salon_payment,
+            staff_payment,
+            app_commission,
+        });
+
+        let accounts = &ctx.accounts;
+        if accounts.platform.auto_close_reservations {
+            close_reservation_accounts(
+                &accounts.reservation,
+                accounts.escrow_token_account.as_ref(),
+                accounts.token_program.as_ref(),
+                &accounts.client,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Close a settled reservation and return its rent to the client (by anyone)
+    pub fn close_reservation(ctx: Context<CloseReservation>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        require!(
+            accounts.reservation.is_settled(),
+            GlamBookError::ReservationNotSettled
+        );
+
+        close_reservation_accounts(
+            &accounts.reservation,
+            accounts.escrow_token_account.as_ref(),
+            accounts.token_program.as_ref(),
+            &accounts.client,
+        )
+    }
+}
+
+/// Calculate refund amounts based on cancellation policy.
+/// `salon_fee` is already converted into the reservation's payment unit. The client
+/// always gets the tier's `refund_bps`; the salon fee and commission are taken from the
+/// rest, and whatever the fee does not claim goes back to the client as well.
+/// Returns (client refund, salon share, commission), which add up to `amount`.
+fn calculate_refund(
+    amount: u64,
+    refund_bps: u16,
+    salon_fee: u64,
+    commission_bps: u16,
+) -> Result<(u64, u64, u64)> {
+    let client_refund = math::bps(amount, refund_bps)?;
+    let retained = math::sub(amount, client_refund)?;
+
+    // Ensure we don't exceed the amount
+    let actual_salon_fee = salon_fee.min(retained);
+    let app_commission = math::bps(actual_salon_fee, commission_bps)?;
+    let salon_share = math::sub(actual_salon_fee, app_commission)?;
+    let unclaimed = math::sub(retained, actual_salon_fee)?;
+
+    Ok((math::add(client_refund, unclaimed)?, salon_share, app_commission))
+}
+
+/// Current SOL/EUR rate in lamports per EUR, from the configured Pyth feed when set,
+/// otherwise from the admin-pushed price. Either source must be fresher than
+/// `max_staleness_secs`.
+fn sol_lamports_per_eur(
+    price_config: &PriceConfig,
+    price_feed: Option<&UncheckedAccount>,
+    now: i64,
+) -> Result<u64> {
+    let Some(oracle) = price_config.oracle else {
+        require!(
+            math::sub_secs(now, price_config.updated_at)? <= price_config.max_staleness_secs,
+            GlamBookError::StalePrice
+        );
+        return Ok(price_config.lamports_per_eur);
+    };
+
+    let price_feed = price_feed.ok_or(GlamBookError::MissingPriceFeed)?;
+    require!(price_feed.key() == oracle, GlamBookError::InvalidPriceFeed);
+
+    let data = price_feed.try_borrow_data()?;
+    let price = read_pyth_price(&data)?;
+    require!(
+        math::sub_secs(now, price.publish_time)? <= price_config.max_staleness_secs,
+        GlamBookError::StalePrice
+    );
+
+    price.lamports_per_eur()
+}
+
+/// Aggregate price read from a Pyth v2 price account
+struct PythPrice {
+    price: i64,
+    expo: i32,
+    publish_time: i64,
+}
+
+impl PythPrice {
+    /// Convert a price of 1 SOL in EUR (`price * 10^expo`) into lamports per EUR
+    fn lamports_per_eur(&self) -> Result<u64> {
+        require!(self.price > 0, GlamBookError::InvalidPriceFeed);
+        require!(self.expo.abs() <= 18, GlamBookError::InvalidPriceFeed);
+
+        let price = self.price as u128;
+        let scale = 10u128.pow(self.expo.unsigned_abs());
+        let lamports_per_eur = if self.expo < 0 {
+            (LAMPORTS_PER_SOL as u128 * scale) / price
+        } else {
+            LAMPORTS_PER_SOL as u128 / (price * scale)
+        };
+
+        require!(lamports_per_eur > 0, GlamBookError::InvalidPriceFeed);
+        u64::try_from(lamports_per_eur).map_err(|_| error!(GlamBookError::InvalidPriceFeed))
+    }
+}
+
+/// Parse the fields we need from a Pyth v2 price account
+fn read_pyth_price(data: &[u8]) -> Result<PythPrice> {
+    let read = |offset: usize, len: usize| -> Result<&[u8]> {
+        data.get(offset..offset + len)
+            .ok_or_else(|| error!(GlamBookError::InvalidPriceFeed))
+    };
+    let read_u32 = |offset| -> Result<u32> {
+        Ok(u32::from_le_bytes(read(offset, 4)?.try_into().unwrap()))
+    };
+    let read_i64 = |offset| -> Result<i64> {
+        Ok(i64::from_le_bytes(read(offset, 8)?.try_into().unwrap()))
+    };
+
+    require!(read_u32(0)? == PYTH_MAGIC, GlamBookError::InvalidPriceFeed);
+    require!(read_u32(4)? == PYTH_VERSION, GlamBookError::InvalidPriceFeed);
+    require!(read_u32(8)? == PYTH_PRICE_ACCOUNT_TYPE, GlamBookError::InvalidPriceFeed);
+    // Aggregate price status must be Trading
+    require!(read_u32(224)? == PYTH_STATUS_TRADING, GlamBookError::InvalidPriceFeed);
+
+    Ok(PythPrice {
+        price: read_i64(208)?,
+        expo: read_u32(20)? as i32,
+        publish_time: read_i64(96)?,
+    })
+}
+
+/// Escrow and payee accounts of a settlement; see `settlement_accounts!`
+struct SettlementAccounts<'a, 'info> {
+    reservation: &'a Account<'info, Reservation>,
+    escrow_token_account: Option<&'a Account<'info, TokenAccount>>,
+    token_program: Option<&'a Program<'info, Token>>,
+    payout_wallet: &'a AccountInfo<'info>,
+    salon_token_account: Option<&'a Account<'info, TokenAccount>>,
+    staff_wallet: Option<&'a UncheckedAccount<'info>>,
+    staff_token_account: Option<&'a Account<'info, TokenAccount>>,
+    treasury: &'a AccountInfo<'info>,
+    treasury_token_account: Option<&'a Account<'info, TokenAccount>>,
+}
+
+/// Pay out the salon side of a settlement: `salon_share` (after commission) goes to
+/// the salon's payout wallet, less the booked stylist's split, and `app_commission`
+/// to the treasury. `other_parts` are what the caller pays itself (refund, crank
+/// reward); with them the split must add up to `total` before any funds move.
+/// Returns the salon and stylist payments.
+fn pay_out_settlement(
+    accounts: &SettlementAccounts,
+    salon_share: u64,
+    app_commission: u64,
+    total: u64,
+    other_parts: &[u64],
+) -> Result<(u64, u64)> {
+    let staff_payment = accounts.reservation.staff_payment(salon_share)?;
+    let salon_payment = math::sub(salon_share, staff_payment)?;
+    let mut parts = vec![salon_payment, staff_payment, app_commission];
+    parts.extend_from_slice(other_parts);
+    math::require_split(total, &parts)?;
+
+    // Transfer payment to salon payout wallet
+    pay_from_escrow(
+        accounts.reservation,
+        accounts.escrow_token_account,
+        accounts.token_program,
+        accounts.payout_wallet,
+        accounts.salon_token_account,
+        salon_payment,
+    )?;
+
+    // Transfer the stylist's split
+    if staff_payment > 0 {
+        let staff_wallet = accounts.staff_wallet.ok_or(GlamBookError::InvalidStaff)?;
+        pay_from_escrow(
+            accounts.reservation,
+            accounts.escrow_token_account,
+            accounts.token_program,
+            &staff_wallet.to_account_info(),
+            accounts.staff_token_account,
+            staff_payment,
+        )?;
+    }
+
+    // Transfer commission to platform treasury
+    pay_from_escrow(
+        accounts.reservation,
+        accounts.escrow_token_account,
+        accounts.token_program,
+        accounts.treasury,
+        accounts.treasury_token_account,
+        app_commission,
+    )?;
+
+    Ok((salon_payment, staff_payment))
+}
+
+/// Pay `amount` out of a reservation's escrow to `wallet`.
+/// SOL reservations move lamports held by the reservation PDA; token reservations
+/// transfer from the escrow token account into `token_account`, which must be owned by `wallet`.
+fn pay_from_escrow<'info>(
+    reservation: &Account<'info, Reservation>,
+    escrow_token_account: Option<&Account<'info, TokenAccount>>,
+    token_program: Option<&Program<'info, Token>>,
+    wallet: &AccountInfo<'info>,
+    token_account: Option<&Account<'info, TokenAccount>>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let Some(mint) = reservation.payment_mint else {
+        transfer_out(&reservation.to_account_info(), wallet, Some(amount))?;
+        return Ok(());
+    };
+
+    let escrow_token_account = escrow_token_account.ok_or(GlamBookError::MissingTokenAccounts)?;
+    let token_program = token_program.ok_or(GlamBookError::MissingTokenAccounts)?;
+    let token_account = token_account.ok_or(GlamBookError::MissingTokenAccounts)?;
+    require!(
+        token_account.owner == wallet.key() && token_account.mint == mint,
+        GlamBookError::InvalidTokenAccount
+    );
+
+    // Read the live balance; the deserialized account is stale after earlier payouts
+    require!(
+        token::accessor::amount(&escrow_token_account.to_account_info())? >= amount,
+        GlamBookError::InsufficientEscrow
+    );
+
+    // The reservation PDA is the escrow authority
+    let appointment_time = reservation.appointment_time.to_le_bytes();
+    let bump = [reservation.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"reservation",
+        reservation.client.as_ref(),
+        reservation.salon.as_ref(),
+        &appointment_time,
+        &bump,
+    ]];
+
+    let cpi_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::Transfer {
+            from: escrow_token_account.to_account_info(),
+            to: token_account.to_account_info(),
+            authority: reservation.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_context, amount)
+}
+
+/// Move lamports out of an account owned by this program (reservation escrow or
+/// salon bond). `Some(amount)` fails with `InsufficientEscrow` unless the account
+/// stays rent-exempt; `None` drains the whole balance of an account being closed.
+/// Returns the lamports moved.
+fn transfer_out(from: &AccountInfo, to: &AccountInfo, amount: Option<u64>) -> Result<u64> {
+    let balance = from.lamports();
+    let amount = match amount {
+        Some(amount) => {
+            let rent_exempt_minimum = Rent::get()?.minimum_balance(from.data_len());
+            math::require_rent_floor(balance, amount, rent_exempt_minimum)?;
+            amount
+        }
+        None => balance,
+    };
+
+    let to_balance = math::add(to.lamports(), amount)?;
+    **from.try_borrow_mut_lamports()? = math::sub(balance, amount)?;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(amount)
+}
+
+/// Complete a reservation, keeping `amount_to_salon` for the salon and refunding
+/// the rest to the client right away. The salon's part is held for the dispute
+/// window like any completion, so the reservation amount shrinks to that part.
+fn settle_completion(ctx: Context<CompleteReservation>, amount_to_salon: u64) -> Result<()> {
+    ctx.accounts.platform.require_not_paused(PAUSE_PAYOUTS)?;
+
+    let reservation = &mut ctx.accounts.reservation;
+    let clock = Clock::get()?;
+
+    // Must be confirmed status
+    require!(
+        reservation.status == ReservationStatus::Confirmed,
+        GlamBookError::InvalidReservationStatus
+    );
+
+    // Can only complete after appointment time
+    require!(
+        clock.unix_timestamp >= reservation.appointment_time,
+        GlamBookError::AppointmentNotYetDue
+    );
+
+    let amount = reservation.amount;
+    require!(amount_to_salon <= amount, GlamBookError::InvalidPartialAmount);
+
+    reservation.status = ReservationStatus::Completed;
+    reservation.completed_at = Some(clock.unix_timestamp);
+    reservation.amount = amount_to_salon;
+
+    // The salon share is split with the booked stylist
+    let client_refund = math::sub(amount, amount_to_salon)?;
+    let app_commission = math::bps(amount_to_salon, ctx.accounts.platform.commission_bps)?;
+    let salon_share = math::sub(amount_to_salon, app_commission)?;
+
+    // Hold the payout while the client can still dispute
+    let dispute_window_secs = ctx.accounts.platform.dispute_window_secs;
+    let (salon_payment, staff_payment) = if dispute_window_secs > 0 {
+        reservation.payout_held_until =
+            Some(math::add_secs(clock.unix_timestamp, dispute_window_secs)?);
+        math::require_split(amount, &[client_refund, salon_share, app_commission])?;
+        let staff_payment = reservation.staff_payment(salon_share)?;
+        (math::sub(salon_share, staff_payment)?, staff_payment)
+    } else {
+        let accounts = &ctx.accounts;
+        let (salon_payment, staff_payment) = pay_out_settlement(
+            &settlement_accounts!(accounts),
+            salon_share,
+            app_commission,
+            amount,
+            &[client_refund],
+        )?;
+
+        // Update salon earnings
+        let salon = &mut ctx.accounts.salon;
+        salon.total_earnings = math::add(salon.total_earnings, salon_payment)?;
+        (salon_payment, staff_payment)
+    };
+
+    // Transfer the unused part back to the client
+    let accounts = &ctx.accounts;
+    if client_refund > 0 {
+        let client = accounts.client.as_ref().ok_or(GlamBookError::InvalidClient)?;
+        pay_from_escrow(
+            &accounts.reservation,
+            accounts.escrow_token_account.as_ref(),
+            accounts.token_program.as_ref(),
+            &client.to_account_info(),
+            accounts.client_token_account.as_ref(),
+            client_refund,
+        )?;
+    }
+
+    let reservation = &ctx.accounts.reservation;
+    if client_refund > 0 {
+        emit!(ReservationPartiallyCompleted {
+            reservation: reservation.key(),
+            amount_to_salon,
+            client_refund,
+            salon_payment,
+            staff_payment,
+            app_commission,
+            payout_held_until: reservation.payout_held_until,
+        });
+    } else {
+        emit!(ReservationCompleted {
+            reservation: reservation.key(),
+            salon_payment,
+            staff_payment,
+            app_commission,
+            payout_held_until: reservation.payout_held_until,
+        });
+    }
+
+    // Without a dispute window the payout is final, so the reservation is settled
+    if accounts.platform.auto_close_reservations && reservation.payout_held_until.is_none() {
+        let client = accounts.client.as_ref().ok_or(GlamBookError::InvalidClient)?;
+        close_reservation_accounts(
+            reservation,
+            accounts.escrow_token_account.as_ref(),
+            accounts.token_program.as_ref(),
+            &client.to_account_info(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Move `reservation`'s booking from `schedule` to `new_appointment_time`, keyed by the
+/// local day at `utc_offset_minutes`. `new_schedule` must be passed exactly when the new
+/// time falls on another day; it is set up for the same resource on first use.
+fn move_booking(
+    schedule: &mut DaySchedule,
+    new_schedule: Option<&mut DaySchedule>,
+    new_schedule_bump: u8,
+    reservation: &Reservation,
+    new_appointment_time: i64,
+    utc_offset_minutes: i16,
+    capacity: u8,
+) -> Result<()> {
+    schedule.release(reservation)?;
+
+    let new_day = DaySchedule::local_day(new_appointment_time, utc_offset_minutes)?;
+    let target = if new_day == schedule.day {
+        require!(new_schedule.is_none(), GlamBookError::InvalidSchedule);
+        schedule
+    } else {
+        let new_schedule = new_schedule.ok_or(GlamBookError::InvalidSchedule)?;
+        if new_schedule.resource == Pubkey::default() {
+            new_schedule.resource = schedule.resource;
+            new_schedule.day = new_day;
+            new_schedule.utc_offset_minutes = utc_offset_minutes;
+            new_schedule.bump = new_schedule_bump;
+        }
+        new_schedule
+    };
+    target.reserve_booking(reservation, new_appointment_time, capacity)
+}
+
+/// Close a settled reservation and its escrow token account, sending the rent to
+/// `client`, who paid for both at booking. Emits the final state for auditing.
+fn close_reservation_accounts<'info>(
+    reservation: &Account<'info, Reservation>,
+    escrow_token_account: Option<&Account<'info, TokenAccount>>,
+    token_program: Option<&Program<'info, Token>>,
+    client: &AccountInfo<'info>,
+) -> Result<()> {
+    let mut rent_returned = 0;
+
+    if reservation.payment_mint.is_some() {
+        let escrow_token_account =
+            escrow_token_account.ok_or(GlamBookError::MissingTokenAccounts)?;
+        let token_program = token_program.ok_or(GlamBookError::MissingTokenAccounts)?;
+        rent_returned = escrow_token_account.to_account_info().lamports();
+
+        let appointment_time = reservation.appointment_time.to_le_bytes();
+        let bump = [reservation.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &appointment_time,
+            &bump,
+        ]];
+
+        let cpi_context = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::CloseAccount {
+                account: escrow_token_account.to_account_info(),
+                destination: client.clone(),
+                authority: reservation.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::close_account(cpi_context)?;
+    }
+
+    let drained = transfer_out(&reservation.to_account_info(), client, None)?;
+    rent_returned = math::add(rent_returned, drained)?;
+
+    emit!(ReservationClosed {
+        reservation: reservation.key(),
+        client: reservation.client,
+        salon: reservation.salon,
+        status: reservation.status.clone(),
+        amount: reservation.amount,
+        appointment_time: reservation.appointment_time,
+        rent_returned,
+    });
+
+    reservation.close(client.clone())
+}
+
+// ============== ACCOUNTS ==============
+
+#[derive(Accounts)]
+pub struct InitializePlatform<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Platform::INIT_SPACE,
+        seeds = [b"platform"],
+        bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePlatform<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump,
+        has_one = admin @ GlamBookError::UnauthorizedAdmin
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump,
+        constraint = platform.pending_admin == Some(new_admin.key()) @ GlamBookError::NotPendingAdmin
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct RegisterSalon<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Owner::INIT_SPACE,
+        seeds = [b"owner", owner.key().as_ref()],
+        bump
+    )]
+    pub owner_registry: Account<'info, Owner>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Salon::INIT_SPACE,
+        seeds = [
+            b"salon",
+            owner.key().as_ref(),
+            &owner_registry.location_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub salon: Account<'info, Salon>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSalon<'info> {
+    #[account(
+        mut,
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddStaff<'info> {
+    #[account(
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Staff::INIT_SPACE,
+        seeds = [b"staff", salon.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub staff: Account<'info, Staff>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStaff<'info> {
+    #[account(
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        mut,
+        seeds = [b"staff", salon.key().as_ref(), staff.wallet.as_ref()],
+        bump = staff.bump,
+        has_one = salon @ GlamBookError::InvalidStaff
+    )]
+    pub staff: Account<'info, Staff>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveStaff<'info> {
+    #[account(
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        mut,
+        seeds = [b"staff", salon.key().as_ref(), staff.wallet.as_ref()],
+        bump = staff.bump,
+        has_one = salon @ GlamBookError::InvalidStaff,
+        close = owner
+    )]
+    pub staff: Account<'info, Staff>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageDelegates<'info> {
+    #[account(
+        mut,
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump
+    )]
+    pub salon: Account<'info, Salon>,
+
+    /// Salon owner, or a manager changing receptionists
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBusinessHours<'info> {
+    #[account(
+        mut,
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SalonHours::INIT_SPACE,
+        seeds = [b"hours", salon.key().as_ref()],
+        bump
+    )]
+    pub salon_hours: Account<'info, SalonHours>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateClosures<'info> {
+    #[account(
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        mut,
+        seeds = [b"hours", salon.key().as_ref()],
+        bump = salon_hours.bump
+    )]
+    pub salon_hours: Account<'info, SalonHours>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddPaymentMint<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump,
+        has_one = admin @ GlamBookError::UnauthorizedAdmin
+    )]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PaymentMintConfig::INIT_SPACE,
+        seeds = [b"payment_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub payment_mint_config: Account<'info, PaymentMintConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePaymentMint<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump,
+        has_one = admin @ GlamBookError::UnauthorizedAdmin
+    )]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_mint", payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Account<'info, PaymentMintConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaymentMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"salon", owner.key().as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        has_one = owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        seeds = [b"payment_mint", payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Option<Account<'info, PaymentMintConfig>>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePriceConfig<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump,
+        has_one = admin @ GlamBookError::UnauthorizedAdmin
+    )]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PriceConfig::INIT_SPACE,
+        seeds = [b"price_config"],
+        bump
+    )]
+    pub price_config: Account<'info, PriceConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceConfig<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump,
+        has_one = admin @ GlamBookError::UnauthorizedAdmin
+    )]
+    pub platform: Account<'info, Platform>,
+
+    #[account(
+        mut,
+        seeds = [b"price_config"],
+        bump = price_config.bump
+    )]
+    pub price_config: Account<'info, PriceConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(service_ids: Vec<u8>, appointment_time: i64)]
+pub struct CreateReservation<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump
+    )]
+    pub salon: Account<'info, Salon>,
+    
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Reservation::INIT_SPACE,
+        seeds = [
+            b"reservation",
+            client.key().as_ref(),
+            salon.key().as_ref(),
+            &appointment_time.to_le_bytes()
+        ],
+        bump
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + DaySchedule::INIT_SPACE,
+        seeds = [
+            b"schedule",
+            salon.key().as_ref(),
+            &salon.local_day(appointment_time)?.to_le_bytes()
+        ],
+        bump
+    )]
+    pub schedule: Account<'info, DaySchedule>,
+
+    /// Required once the salon has set business hours
+    #[account(
+        seeds = [b"hours", salon.key().as_ref()],
+        bump = salon_hours.bump
+    )]
+    pub salon_hours: Option<Account<'info, SalonHours>>,
+
+    /// Stylist booked for the appointment, if any
+    #[account(
+        seeds = [b"staff", salon.key().as_ref(), staff.wallet.as_ref()],
+        bump = staff.bump
+    )]
+    pub staff: Option<Account<'info, Staff>>,
+
+    /// Passed together with `staff`
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + DaySchedule::INIT_SPACE,
+        seeds = [
+            b"schedule",
+            staff.as_ref().map(|staff| staff.key()).unwrap_or_default().as_ref(),
+            &salon.local_day(appointment_time)?.to_le_bytes()
+        ],
+        bump
+    )]
+    pub staff_schedule: Option<Account<'info, DaySchedule>>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    /// Only required when the salon is paid in an SPL token
+    pub payment_mint: Option<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = client,
+        token::mint = payment_mint,
+        token::authority = reservation,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PayBalance<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump,
+        constraint = reservation.client == client.key() @ GlamBookError::InvalidClient
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelReservation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump,
+        constraint = reservation.client == client.key() @ GlamBookError::UnauthorizedCancellation
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.salon.as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, DaySchedule>,
+
+    /// Required when the reservation has a stylist
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.staff_key().as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = staff_schedule.bump
+    )]
+    pub staff_schedule: Option<Account<'info, DaySchedule>>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+    
+    #[account(
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
+    )]
+    pub salon: Account<'info, Salon>,
+
+    /// CHECK: Validated against salon.payout_wallet
+    #[account(
+        mut,
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
+    )]
+    pub payout_wallet: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    /// CHECK: Validated against platform.treasury_wallet
+    #[account(
+        mut,
+        constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"payment_mint", payment_mint_config.mint.as_ref()],
+        bump = payment_mint_config.bump
+    )]
+    pub payment_mint_config: Option<Account<'info, PaymentMintConfig>>,
+
+    #[account(
+        seeds = [b"price_config"],
+        bump = price_config.bump
+    )]
+    pub price_config: Option<Account<'info, PriceConfig>>,
+
+    /// CHECK: Validated against price_config.oracle and parsed as a Pyth price account
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub salon_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_appointment_time: i64)]
+pub struct RescheduleReservation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump,
+        constraint = reservation.client == client.key() @ GlamBookError::UnauthorizedReschedule
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        init,
+        payer = client,
+        space = 8 + Reservation::INIT_SPACE,
+        seeds = [
+            b"reservation",
+            client.key().as_ref(),
+            salon.key().as_ref(),
+            &new_appointment_time.to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_reservation: Account<'info, Reservation>,
+
+    #[account(
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.salon.as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, DaySchedule>,
+
+    /// Only passed when the new appointment is on a different day
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + DaySchedule::INIT_SPACE,
+        seeds = [
+            b"schedule",
+            salon.key().as_ref(),
+            &salon.local_day(new_appointment_time)?.to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_schedule: Option<Account<'info, DaySchedule>>,
+
+    /// Stylist of the reservation; required with the staff schedules when one is booked
+    #[account(
+        constraint = staff.key() == reservation.staff_key() @ GlamBookError::InvalidStaff
+    )]
+    pub staff: Option<Account<'info, Staff>>,
+
+    /// Required when the reservation has a stylist
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.staff_key().as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = staff_schedule.bump
+    )]
+    pub staff_schedule: Option<Account<'info, DaySchedule>>,
+
+    /// Only passed when the reservation has a stylist and the new day differs
+    #[account(
+        init_if_needed,
+        payer = client,
+        space = 8 + DaySchedule::INIT_SPACE,
+        seeds = [
+            b"schedule",
+            reservation.staff_key().as_ref(),
+            &salon.local_day(new_appointment_time)?.to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_staff_schedule: Option<Account<'info, DaySchedule>>,
+
+    /// Required once the salon has set business hours
+    #[account(
+        seeds = [b"hours", salon.key().as_ref()],
+        bump = salon_hours.bump
+    )]
+    pub salon_hours: Option<Account<'info, SalonHours>>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    /// CHECK: Validated against reservation.salon_owner; must also sign when
+    /// the salon's reschedule policy requires approval
+    #[account(
+        constraint = salon_owner.key() == reservation.salon_owner @ GlamBookError::InvalidSalonOwner
+    )]
+    pub salon_owner: AccountInfo<'info>,
+
+    /// CHECK: Validated against salon.payout_wallet
+    #[account(
+        mut,
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
+    )]
+    pub payout_wallet: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+
+    /// CHECK: Validated against platform.treasury_wallet
+    #[account(
+        mut,
+        constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// Only required when the reservation is paid in an SPL token
+    pub payment_mint: Option<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = client,
+        token::mint = payment_mint,
+        token::authority = new_reservation,
+        seeds = [b"escrow", new_reservation.key().as_ref()],
+        bump
+    )]
+    pub new_escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub salon_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SalonCancelReservation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"salon",
+            salon_owner.key().as_ref(),
+            &salon.location_index.to_le_bytes()
+        ],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.salon.as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, DaySchedule>,
+
+    /// Required when the reservation has a stylist
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.staff_key().as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = staff_schedule.bump
+    )]
+    pub staff_schedule: Option<Account<'info, DaySchedule>>,
+
+    #[account(
+        constraint = salon_owner.key() == reservation.salon_owner @ GlamBookError::UnauthorizedSalonCancellation
+    )]
+    pub salon_owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+
+    /// CHECK: Validated against reservation.client
+    #[account(
+        mut,
+        constraint = client.key() == reservation.client @ GlamBookError::InvalidClient
+    )]
+    pub client: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleExpiredReservation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        mut,
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
+    )]
+    pub salon: Account<'info, Salon>,
+
+    /// CHECK: Validated against salon.payout_wallet
+    #[account(
+        mut,
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
+    )]
+    pub payout_wallet: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+
+    /// CHECK: Validated against platform.treasury_wallet
+    #[account(
+        mut,
+        constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    /// CHECK: Validated against the stylist wallet stored on the reservation
+    #[account(
+        mut,
+        constraint = reservation
+            .staff
+            .as_ref()
+            .is_some_and(|assignment| assignment.wallet == staff_wallet.key())
+            @ GlamBookError::InvalidStaff
+    )]
+    pub staff_wallet: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated against reservation.client; receives the rent on auto-close
+    #[account(
+        mut,
+        constraint = client.key() == reservation.client @ GlamBookError::InvalidClient
+    )]
+    pub client: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub salon_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub cranker_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staff_token_account: Option<Account<'info, TokenAccount>>,
 
-    let client_refund = (amount * client_percentage) / 100;
-    let salon_fee = salon_fee_eur * EUR_TO_LAMPORTS;
-    
-    // Ensure we don't exceed the amount
-    let actual_salon_fee = salon_fee.min(amount - client_refund);
-    let app_commission = (actual_salon_fee * APP_COMMISSION_BPS) / 10000;
+    pub token_program: Option<Program<'info, Token>>,
 
-    Ok((client_refund, actual_salon_fee - app_commission, app_commission))
+    pub system_program: Program<'info, System>,
 }
 
-// ============== ACCOUNTS ==============
-
 #[derive(Accounts)]
-pub struct InitializePlatform<'info> {
+pub struct CompleteReservation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump
+    )]
+    pub reservation: Account<'info, Reservation>,
+    
+    #[account(
+        mut,
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
+    )]
+    pub salon: Account<'info, Salon>,
+
+    /// Salon owner or one of the salon's delegates
+    #[account(
+        constraint = salon.role_of(&operator.key()).is_some() @ GlamBookError::UnauthorizedCompletion
+    )]
+    pub operator: Signer<'info>,
+    
+    /// CHECK: Validated against salon.payout_wallet
+    #[account(
+        mut,
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
+    )]
+    pub payout_wallet: AccountInfo<'info>,
+    
     #[account(
-        init,
-        payer = admin,
-        space = 8 + Platform::INIT_SPACE,
         seeds = [b"platform"],
-        bump
+        bump = platform.bump
     )]
     pub platform: Account<'info, Platform>,
     
+    /// CHECK: Validated against platform.treasury_wallet
+    #[account(
+        mut,
+        constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
+    )]
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Validated against the stylist wallet stored on the reservation
+    #[account(
+        mut,
+        constraint = reservation
+            .staff
+            .as_ref()
+            .is_some_and(|assignment| assignment.wallet == staff_wallet.key())
+            @ GlamBookError::InvalidStaff
+    )]
+    pub staff_wallet: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated against reservation.client; needed for partial completions and,
+    /// with auto-close on and no dispute window, to receive the rent back
+    #[account(
+        mut,
+        constraint = client.key() == reservation.client @ GlamBookError::InvalidClient
+    )]
+    pub client: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub salon_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staff_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(name: String)]
-pub struct RegisterSalon<'info> {
+pub struct ConfirmService<'info> {
     #[account(
-        init,
-        payer = owner,
-        space = 8 + Salon::INIT_SPACE,
-        seeds = [b"salon", owner.key().as_ref()],
-        bump
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump,
+        constraint = reservation.client == client.key() @ GlamBookError::InvalidClient
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        mut,
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
     )]
     pub salon: Account<'info, Salon>,
-    
+
+    /// Receives the rent on auto-close
     #[account(mut)]
-    pub owner: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub client: Signer<'info>,
 
-#[derive(Accounts)]
-#[instruction(service_id: u8, appointment_time: i64)]
-pub struct CreateReservation<'info> {
+    /// CHECK: Validated against salon.payout_wallet
     #[account(
         mut,
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
+    )]
+    pub payout_wallet: AccountInfo<'info>,
+
+    #[account(
         seeds = [b"platform"],
         bump = platform.bump
     )]
     pub platform: Account<'info, Platform>,
-    
+
+    /// CHECK: Validated against platform.treasury_wallet
     #[account(
         mut,
-        seeds = [b"salon", salon.owner.as_ref()],
-        bump = salon.bump
+        constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
     )]
-    pub salon: Account<'info, Salon>,
-    
+    pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Validated against the stylist wallet stored on the reservation
     #[account(
-        init,
-        payer = client,
-        space = 8 + Reservation::INIT_SPACE,
-        seeds = [
-            b"reservation",
-            client.key().as_ref(),
-            salon.key().as_ref(),
-            &appointment_time.to_le_bytes()
-        ],
+        mut,
+        constraint = reservation
+            .staff
+            .as_ref()
+            .is_some_and(|assignment| assignment.wallet == staff_wallet.key())
+            @ GlamBookError::InvalidStaff
+    )]
+    pub staff_wallet: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
         bump
     )]
-    pub reservation: Account<'info, Reservation>,
-    
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
     #[account(mut)]
-    pub client: Signer<'info>,
-    
+    pub salon_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staff_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CancelReservation<'info> {
+pub struct Tip<'info> {
     #[account(
         mut,
         seeds = [
@@ -420,38 +3706,56 @@ pub struct CancelReservation<'info> {
             &reservation.appointment_time.to_le_bytes()
         ],
         bump = reservation.bump,
-        constraint = reservation.client == client.key() @ GlamBookError::UnauthorizedCancellation
+        constraint = reservation.client == client.key() @ GlamBookError::InvalidClient
     )]
     pub reservation: Account<'info, Reservation>,
-    
+
+    #[account(
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
+    )]
+    pub salon: Account<'info, Salon>,
+
     #[account(mut)]
     pub client: Signer<'info>,
-    
-    /// CHECK: Validated against reservation.salon_owner
+
+    /// CHECK: Validated against salon.payout_wallet
     #[account(
         mut,
-        constraint = salon_owner.key() == reservation.salon_owner @ GlamBookError::InvalidSalonOwner
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
     )]
-    pub salon_owner: AccountInfo<'info>,
-    
+    pub payout_wallet: AccountInfo<'info>,
+
+    /// CHECK: Validated against the stylist wallet stored on the reservation
+    #[account(
+        mut,
+        constraint = reservation
+            .staff
+            .as_ref()
+            .is_some_and(|assignment| assignment.wallet == staff_wallet.key())
+            @ GlamBookError::InvalidStaff
+    )]
+    pub staff_wallet: Option<UncheckedAccount<'info>>,
+
     #[account(
         seeds = [b"platform"],
         bump = platform.bump
     )]
     pub platform: Account<'info, Platform>,
-    
+
     /// CHECK: Validated against platform.treasury_wallet
     #[account(
         mut,
         constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
     )]
     pub treasury: AccountInfo<'info>,
-    
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct CompleteReservation<'info> {
+pub struct MarkNoShow<'info> {
     #[account(
         mut,
         seeds = [
@@ -466,16 +3770,24 @@ pub struct CompleteReservation<'info> {
     
     #[account(
         mut,
-        seeds = [b"salon", salon_owner.key().as_ref()],
-        bump = salon.bump
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
     )]
     pub salon: Account<'info, Salon>,
+
+    /// Salon owner or one of the salon's delegates
+    #[account(
+        constraint = salon.role_of(&operator.key()).is_some() @ GlamBookError::UnauthorizedNoShow
+    )]
+    pub operator: Signer<'info>,
     
+    /// CHECK: Validated against salon.payout_wallet
     #[account(
         mut,
-        constraint = salon_owner.key() == reservation.salon_owner @ GlamBookError::UnauthorizedCompletion
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
     )]
-    pub salon_owner: Signer<'info>,
+    pub payout_wallet: AccountInfo<'info>,
     
     #[account(
         seeds = [b"platform"],
@@ -489,12 +3801,157 @@ pub struct CompleteReservation<'info> {
         constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
     )]
     pub treasury: AccountInfo<'info>,
+
+    /// CHECK: Validated against reservation.client; needed with auto-close on and no
+    /// dispute window, to receive the rent back
+    #[account(
+        mut,
+        constraint = client.key() == reservation.client @ GlamBookError::InvalidClient
+    )]
+    pub client: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub salon_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct MarkNoShow<'info> {
+pub struct RefundLineItem<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    #[account(
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
+    )]
+    pub salon: Account<'info, Salon>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.salon.as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = schedule.bump
+    )]
+    pub schedule: Account<'info, DaySchedule>,
+
+    /// Required when the reservation has a stylist
+    #[account(
+        mut,
+        seeds = [
+            b"schedule",
+            reservation.staff_key().as_ref(),
+            &reservation.schedule_day.to_le_bytes()
+        ],
+        bump = staff_schedule.bump
+    )]
+    pub staff_schedule: Option<Account<'info, DaySchedule>>,
+
+    /// Salon owner or manager
+    #[account(
+        constraint = matches!(
+            salon.role_of(&operator.key()),
+            Some(SalonRole::Owner | SalonRole::Manager)
+        ) @ GlamBookError::UnauthorizedLineItemRefund
+    )]
+    pub operator: Signer<'info>,
+
+    /// CHECK: Validated against reservation.client
+    #[account(
+        mut,
+        constraint = client.key() == reservation.client @ GlamBookError::InvalidClient
+    )]
+    pub client: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseReservation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    /// CHECK: Validated against reservation.client, who paid the rent
+    #[account(
+        mut,
+        constraint = client.key() == reservation.client @ GlamBookError::InvalidClient
+    )]
+    pub client: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"reservation",
+            reservation.client.as_ref(),
+            reservation.salon.as_ref(),
+            &reservation.appointment_time.to_le_bytes()
+        ],
+        bump = reservation.bump,
+        constraint = reservation.client == client.key() @ GlamBookError::UnauthorizedDispute
+    )]
+    pub reservation: Account<'info, Reservation>,
+
+    pub client: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
     #[account(
         mut,
         seeds = [
@@ -506,95 +3963,688 @@ pub struct MarkNoShow<'info> {
         bump = reservation.bump
     )]
     pub reservation: Account<'info, Reservation>,
-    
+
     #[account(
         mut,
-        seeds = [b"salon", salon_owner.key().as_ref()],
-        bump = salon.bump
+        seeds = [b"salon", salon.owner.as_ref(), &salon.location_index.to_le_bytes()],
+        bump = salon.bump,
+        constraint = salon.key() == reservation.salon @ GlamBookError::InvalidSalon
     )]
     pub salon: Account<'info, Salon>,
-    
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump,
+        has_one = arbiter @ GlamBookError::UnauthorizedArbiter
+    )]
+    pub platform: Account<'info, Platform>,
+
+    pub arbiter: Signer<'info>,
+
+    /// CHECK: Validated against reservation.client
     #[account(
         mut,
-        constraint = salon_owner.key() == reservation.salon_owner @ GlamBookError::UnauthorizedNoShow
+        constraint = client.key() == reservation.client @ GlamBookError::InvalidClient
     )]
-    pub salon_owner: Signer<'info>,
-    
+    pub client: AccountInfo<'info>,
+
+    /// CHECK: Validated against salon.payout_wallet
     #[account(
-        seeds = [b"platform"],
-        bump = platform.bump
+        mut,
+        constraint = payout_wallet.key() == salon.payout_wallet @ GlamBookError::InvalidPayoutWallet
     )]
-    pub platform: Account<'info, Platform>,
-    
+    pub payout_wallet: AccountInfo<'info>,
+
     /// CHECK: Validated against platform.treasury_wallet
     #[account(
         mut,
         constraint = treasury.key() == platform.treasury_wallet @ GlamBookError::InvalidTreasury
     )]
     pub treasury: AccountInfo<'info>,
-    
+
+    /// CHECK: Validated against the stylist wallet stored on the reservation
+    #[account(
+        mut,
+        constraint = reservation
+            .staff
+            .as_ref()
+            .is_some_and(|assignment| assignment.wallet == staff_wallet.key())
+            @ GlamBookError::InvalidStaff
+    )]
+    pub staff_wallet: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", reservation.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub client_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub salon_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub staff_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
     pub system_program: Program<'info, System>,
 }
 
-// ============== STATE ==============
+// ============== STATE ==============
+
+#[account]
+#[derive(InitSpace)]
+pub struct Platform {
+    pub admin: Pubkey,
+    pub treasury_wallet: Pubkey,
+    pub pending_admin: Option<Pubkey>, // Set by propose_admin until accepted
+    pub paused_operations: u8,         // PAUSE_* bitmask
+    pub total_reservations: u64,
+    pub total_volume: u64,
+    pub commission_bps: u16,
+    pub no_show_grace_secs: i64,
+    pub default_cancellation_policy: CancellationPolicy, // Given to newly registered salons
+    pub settlement_window_secs: i64, // After the appointment, anyone may settle
+    pub expired_settlement: ExpiredSettlement,
+    pub crank_reward_bps: u16, // Share of the commission paid to whoever settles
+    pub dispute_window_secs: i64, // Payouts are held this long after completion/no-show
+    pub arbiter: Pubkey,          // Resolves disputes
+    pub auto_close_reservations: bool, // Settlement closes the reservation and refunds its rent
+    pub tip_fee_bps: u16, // Platform fee on tips, 0 = tips are commission-free
+    pub bump: u8,
+}
+
+impl Platform {
+    pub fn require_not_paused(&self, operation: u8) -> Result<()> {
+        require!(
+            self.paused_operations & operation == 0,
+            GlamBookError::OperationPaused
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PlatformConfigParams {
+    pub commission_bps: Option<u16>,
+    pub no_show_grace_secs: Option<i64>,
+    pub default_cancellation_policy: Option<CancellationPolicy>,
+    pub settlement_window_secs: Option<i64>,
+    pub expired_settlement: Option<ExpiredSettlement>,
+    pub crank_reward_bps: Option<u16>,
+    pub dispute_window_secs: Option<i64>,
+    pub auto_close_reservations: Option<bool>,
+    pub tip_fee_bps: Option<u16>,
+}
+
+/// Outcome of settle_expired_reservation; both pay the salon
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ExpiredSettlement {
+    Complete,
+    NoShow,
+}
+
+/// Registry of the salon locations run by one wallet
+#[account]
+#[derive(InitSpace)]
+pub struct Owner {
+    pub wallet: Pubkey,
+    pub location_count: u16, // Index given to the next salon registered
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Salon {
+    pub owner: Pubkey,
+    pub payout_wallet: Pubkey, // Receives the salon's payouts, the owner by default
+    pub location_index: u16, // Position among the owner's salons, part of the PDA seeds
+    #[max_len(64)]
+    pub name: String,
+    pub is_active: bool,
+    pub total_earnings: u64,
+    pub reservation_count: u64,
+    #[max_len(10)]
+    pub services: Vec<Service>,
+    pub payment_mint: Option<Pubkey>, // None = native SOL
+    pub cancellation_policy: CancellationPolicy,
+    pub chairs: u8, // Clients that can be served at the same time
+    pub has_business_hours: bool, // Bookings are checked against SalonHours
+    pub reschedule_policy: ReschedulePolicy,
+    pub bond_lamports: u64,       // SOL held by this account for client compensation
+    pub salon_cancellations: u64, // Reservations cancelled by the salon
+    #[max_len(8)]
+    pub delegates: Vec<SalonDelegate>, // Front desk wallets besides the owner
+    pub deposit_bps: u16, // Share of the price escrowed at booking, 10000 = full prepayment
+    pub utc_offset_minutes: i16, // From the business hours; schedules use the local day
+    pub bump: u8,
+}
+
+impl Salon {
+    /// Local day of `time`, which keys the salon's and its stylists' schedules
+    pub fn local_day(&self, time: i64) -> Result<i64> {
+        DaySchedule::local_day(time, self.utc_offset_minutes)
+    }
+
+    /// Role of `wallet` at this salon, None for outsiders
+    pub fn role_of(&self, wallet: &Pubkey) -> Option<SalonRole> {
+        if *wallet == self.owner {
+            return Some(SalonRole::Owner);
+        }
+        self.delegates
+            .iter()
+            .find(|delegate| delegate.wallet == *wallet)
+            .map(|delegate| delegate.role.clone())
+    }
+
+    /// Owners manage every delegate, managers only receptionists
+    pub fn can_manage_role(&self, authority: &Pubkey, role: &SalonRole) -> bool {
+        match self.role_of(authority) {
+            Some(SalonRole::Owner) => true,
+            Some(SalonRole::Manager) => *role == SalonRole::Receptionist,
+            _ => false,
+        }
+    }
+}
+
+/// Who may act for a salon. Every role can complete reservations and mark
+/// no-shows; services, payouts and settings stay with the owner.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum SalonRole {
+    Owner,
+    Manager,      // Also adds and removes receptionists
+    Receptionist,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct SalonDelegate {
+    pub wallet: Pubkey,
+    pub role: SalonRole,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Service {
+    pub id: u8,
+    #[max_len(32)]
+    pub name: String,
+    pub price_lamports: u64, // base units of the salon's payment mint when set
+    pub duration_minutes: u16,
+    pub is_active: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ServiceInput {
+    pub id: u8,
+    pub name: String,
+    pub price_lamports: u64,
+    pub duration_minutes: u16,
+}
+
+/// New price of a service in base units of the new payment mint, or lamports for SOL
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ServicePrice {
+    pub service_id: u8,
+    pub price: u64,
+}
+
+/// Refund applied when cancelling more than `min_notice_secs` before the appointment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct CancellationTier {
+    pub min_notice_secs: i64,
+    pub refund_bps: u16,
+    pub fee_eur_cents: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct CancellationPolicy {
+    #[max_len(5)]
+    pub tiers: Vec<CancellationTier>, // Sorted by descending min_notice_secs
+    pub late_fee_eur_cents: u64,      // No refund, this fee, when no tier applies
+}
+
+impl Default for CancellationPolicy {
+    /// > 48h: 100% refund; 24-48h: 80%, €2 fee; < 24h: 50%, €5 fee; after: 0%, €10 fee
+    fn default() -> Self {
+        Self {
+            tiers: vec![
+                CancellationTier {
+                    min_notice_secs: HOURS_48,
+                    refund_bps: 10_000,
+                    fee_eur_cents: 0,
+                },
+                CancellationTier {
+                    min_notice_secs: HOURS_24,
+                    refund_bps: 8_000,
+                    fee_eur_cents: 200,
+                },
+                CancellationTier {
+                    min_notice_secs: 0,
+                    refund_bps: 5_000,
+                    fee_eur_cents: 500,
+                },
+            ],
+            late_fee_eur_cents: 1_000,
+        }
+    }
+}
+
+impl CancellationPolicy {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.tiers.is_empty() && self.tiers.len() <= MAX_CANCELLATION_TIERS,
+            GlamBookError::InvalidCancellationPolicy
+        );
+
+        for tier in self.tiers.iter() {
+            require!(
+                tier.min_notice_secs >= 0 && tier.refund_bps as u64 <= BPS_DENOMINATOR,
+                GlamBookError::InvalidCancellationPolicy
+            );
+        }
+
+        // Less notice must never mean a better deal for the client
+        for pair in self.tiers.windows(2) {
+            require!(
+                pair[0].min_notice_secs > pair[1].min_notice_secs
+                    && pair[0].refund_bps >= pair[1].refund_bps
+                    && pair[0].fee_eur_cents <= pair[1].fee_eur_cents,
+                GlamBookError::InvalidCancellationPolicy
+            );
+        }
+
+        let last_tier = &self.tiers[self.tiers.len() - 1];
+        require!(
+            self.late_fee_eur_cents >= last_tier.fee_eur_cents,
+            GlamBookError::InvalidCancellationPolicy
+        );
+
+        Ok(())
+    }
+
+    /// Refund (bps) and salon fee (EUR cents) for cancelling `time_until_appointment` seconds ahead
+    pub fn terms(&self, time_until_appointment: i64) -> (u16, u64) {
+        self.tiers
+            .iter()
+            .find(|tier| time_until_appointment > tier.min_notice_secs)
+            .map(|tier| (tier.refund_bps, tier.fee_eur_cents))
+            .unwrap_or((0, self.late_fee_eur_cents))
+    }
+}
+
+/// When and at what cost a client may move a booking
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct ReschedulePolicy {
+    pub min_notice_secs: i64, // No rescheduling closer to the appointment than this
+    pub fee_bps: u16,         // Share of the escrow kept by the salon
+    pub requires_salon_approval: bool, // Salon owner must co-sign
+}
+
+impl Default for ReschedulePolicy {
+    /// Free up to 24h before the appointment, no approval needed
+    fn default() -> Self {
+        Self {
+            min_notice_secs: HOURS_24,
+            fee_bps: 0,
+            requires_salon_approval: false,
+        }
+    }
+}
+
+/// Stylist working at a salon, booked with their own schedule
+#[account]
+#[derive(InitSpace)]
+pub struct Staff {
+    pub salon: Pubkey,
+    pub wallet: Pubkey, // Receives the stylist's split
+    #[max_len(64)]
+    pub name: String,
+    #[max_len(10)]
+    pub service_ids: Vec<u8>, // Salon services this stylist performs
+    pub commission_split_bps: u16, // Stylist's share of the salon payment
+    pub capacity: u8, // Clients the stylist serves at the same time
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl Staff {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.name.len() <= 64, GlamBookError::NameTooLong);
+        require!(
+            self.service_ids.len() <= MAX_SERVICES,
+            GlamBookError::TooManyServices
+        );
+        require!(
+            self.commission_split_bps as u64 <= BPS_DENOMINATOR,
+            GlamBookError::InvalidStaffSplit
+        );
+        require!(self.capacity > 0, GlamBookError::InvalidChairCount);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StaffInput {
+    pub name: String,
+    pub service_ids: Vec<u8>,
+    pub commission_split_bps: u16,
+    pub capacity: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StaffUpdate {
+    pub name: Option<String>,
+    pub service_ids: Option<Vec<u8>>,
+    pub commission_split_bps: Option<u16>,
+    pub capacity: Option<u8>,
+    pub is_active: Option<bool>,
+}
+
+/// Stylist booked on a reservation, captured at booking time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct StaffAssignment {
+    pub staff: Pubkey,
+    pub wallet: Pubkey,
+    pub commission_split_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ServiceUpdate {
+    pub name: Option<String>,
+    pub price_lamports: Option<u64>,
+    pub duration_minutes: Option<u16>,
+    pub is_active: Option<bool>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Reservation {
+    pub client: Pubkey,
+    pub salon: Pubkey,
+    pub salon_owner: Pubkey,
+    #[max_len(5)]
+    pub line_items: Vec<LineItem>, // Booked services, performed back to back
+    pub amount: u64, // Escrowed: deposit plus any paid balance, minus refunded line items
+    pub balance_due: u64, // Not escrowed, paid at the venue or via pay_balance
+    pub payment_mint: Option<Pubkey>, // None = native SOL
+    pub cancellation_policy: CancellationPolicy, // Snapshot of the salon policy at booking
+    pub appointment_time: i64,
+    pub duration_minutes: u16,
+    pub created_at: i64,
+    pub status: ReservationStatus,
+    pub cancelled_at: Option<i64>,
+    pub completed_at: Option<i64>,
+    pub payout_held_until: Option<i64>, // Dispute window end while the payout is held
+    pub evidence_hash: Option<[u8; 32]>, // Hash of the client's off-chain dispute evidence
+    pub staff: Option<StaffAssignment>, // Booked stylist, None = any
+    pub tips: u64, // Lamports tipped by the client, paid straight to the salon or stylist
+    pub schedule_day: i64, // Local day of the DaySchedule accounts holding the booking
+    pub bump: u8,
+}
+
+/// One service of a reservation, captured at booking time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct LineItem {
+    pub service_id: u8,
+    #[max_len(32)]
+    pub name: String,
+    pub amount: u64,
+    pub duration_minutes: u16,
+    pub refunded: bool, // Refunded on its own while the rest of the bundle stays booked
+}
+
+impl Reservation {
+    /// Staff account of the booked stylist, or the default key without one
+    pub fn staff_key(&self) -> Pubkey {
+        self.staff
+            .as_ref()
+            .map(|assignment| assignment.staff)
+            .unwrap_or_default()
+    }
+
+    /// Stylist's split of the salon share. Only completed services are split, also
+    /// when their completion was disputed; no-show payouts stay with the salon.
+    pub fn staff_payment(&self, salon_share: u64) -> Result<u64> {
+        let completed = match self.status {
+            ReservationStatus::Completed => true,
+            ReservationStatus::Disputed | ReservationStatus::DisputeResolved => {
+                self.completed_at.is_some()
+            }
+            _ => false,
+        };
+        match &self.staff {
+            Some(assignment) if completed => {
+                math::bps(salon_share, assignment.commission_split_bps)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    /// No funds left to move: the reservation can be closed
+    pub fn is_settled(&self) -> bool {
+        match self.status {
+            ReservationStatus::Confirmed | ReservationStatus::Disputed => false,
+            ReservationStatus::Completed | ReservationStatus::NoShow => {
+                self.payout_held_until.is_none()
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Bookings per 15 minute slot for one salon or stylist on one salon-local day
+#[account]
+#[derive(InitSpace)]
+pub struct DaySchedule {
+    pub resource: Pubkey, // Salon or stylist whose capacity is booked
+    pub day: i64,         // Local days since the Unix epoch
+    pub utc_offset_minutes: i16, // Salon offset the day was keyed with
+    pub slots: [u8; SLOTS_PER_DAY], // Bookings per slot
+    pub bump: u8,
+}
+
+impl DaySchedule {
+    /// Local day of `time` for a salon at `utc_offset_minutes`, used in the schedule seeds
+    pub fn local_day(time: i64, utc_offset_minutes: i16) -> Result<i64> {
+        Ok(math::add_secs(time, utc_offset_minutes as i64 * 60)?.div_euclid(SECONDS_PER_DAY))
+    }
+
+    /// Unix time of local midnight starting the day
+    fn day_start(&self) -> Result<i64> {
+        let midnight = self
+            .day
+            .checked_mul(SECONDS_PER_DAY)
+            .ok_or_else(|| error!(GlamBookError::MathOverflow))?;
+        math::sub_secs(midnight, self.utc_offset_minutes as i64 * 60)
+    }
+
+    /// Slots overlapped by an appointment; at least one slot, never past local midnight
+    fn slot_range(&self, appointment_time: i64, duration_minutes: u16) -> Result<Range<usize>> {
+        let start = math::sub_secs(appointment_time, self.day_start()?)?;
+        let end = math::add_secs(start, duration_minutes as i64 * 60)?;
+        require!(
+            (0..SECONDS_PER_DAY).contains(&start),
+            GlamBookError::InvalidSchedule
+        );
+        require!(end <= SECONDS_PER_DAY, GlamBookError::AppointmentCrossesDay);
+
+        let first = (start / SLOT_SECONDS) as usize;
+        let last = ((end + SLOT_SECONDS - 1) / SLOT_SECONDS) as usize;
+        Ok(first..last.max(first + 1))
+    }
+
+    /// Slots `reservation` holds when it starts at `appointment_time`: those its services
+    /// still booked run through, skipping line item `skip`. Matches `slot_range` while no
+    /// service is refunded.
+    fn held_slots(
+        &self,
+        reservation: &Reservation,
+        appointment_time: i64,
+        skip: Option<usize>,
+    ) -> Result<Vec<usize>> {
+        let range = self.slot_range(appointment_time, reservation.duration_minutes)?;
+        if reservation.duration_minutes == 0 {
+            return Ok(range.collect());
+        }
+
+        // Services run back to back, so each one starts where the one before ended
+        let mut services = Vec::with_capacity(reservation.line_items.len());
+        let mut start = appointment_time;
+        for (index, item) in reservation.line_items.iter().enumerate() {
+            let end = math::add_secs(start, item.duration_minutes as i64 * 60)?;
+            if !item.refunded && skip != Some(index) {
+                services.push(start..end);
+            }
+            start = end;
+        }
+
+        let day_start = self.day_start()?;
+        let mut held = Vec::with_capacity(range.len());
+        for slot in range {
+            let slot_start = math::add_secs(day_start, slot as i64 * SLOT_SECONDS)?;
+            let slot_end = math::add_secs(slot_start, SLOT_SECONDS)?;
+            if services
+                .iter()
+                .any(|service| service.start < slot_end && service.end > slot_start)
+            {
+                held.push(slot);
+            }
+        }
+        Ok(held)
+    }
+
+    fn claim(&mut self, slots: Vec<usize>, capacity: u8) -> Result<()> {
+        require!(
+            slots.iter().all(|&slot| self.slots[slot] < capacity),
+            GlamBookError::SlotUnavailable
+        );
+        for slot in slots {
+            self.slots[slot] += 1;
+        }
+        Ok(())
+    }
+
+    fn free(&mut self, slots: Vec<usize>) {
+        for slot in slots {
+            self.slots[slot] = self.slots[slot].saturating_sub(1);
+        }
+    }
+
+    pub fn reserve(
+        &mut self,
+        appointment_time: i64,
+        duration_minutes: u16,
+        capacity: u8,
+    ) -> Result<()> {
+        let range = self.slot_range(appointment_time, duration_minutes)?;
+        self.claim(range.collect(), capacity)
+    }
+
+    /// Book the slots `reservation` holds, moved to `appointment_time`
+    pub fn reserve_booking(
+        &mut self,
+        reservation: &Reservation,
+        appointment_time: i64,
+        capacity: u8,
+    ) -> Result<()> {
+        let slots = self.held_slots(reservation, appointment_time, None)?;
+        self.claim(slots, capacity)
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Platform {
-    pub admin: Pubkey,
-    pub treasury_wallet: Pubkey,
-    pub total_reservations: u64,
-    pub total_volume: u64,
-    pub bump: u8,
+    /// Free the slots `reservation` holds
+    pub fn release(&mut self, reservation: &Reservation) -> Result<()> {
+        let slots = self.held_slots(reservation, reservation.appointment_time, None)?;
+        self.free(slots);
+        Ok(())
+    }
+
+    /// Free the slots only line item `index` of `reservation` holds; call before marking
+    /// it refunded. Slots shared with a neighbouring service stay booked.
+    pub fn release_line_item(&mut self, reservation: &Reservation, index: usize) -> Result<()> {
+        let held = self.held_slots(reservation, reservation.appointment_time, None)?;
+        let kept = self.held_slots(reservation, reservation.appointment_time, Some(index))?;
+        self.free(held.into_iter().filter(|slot| !kept.contains(slot)).collect());
+        Ok(())
+    }
 }
 
+/// Weekly opening hours and closures of a salon
 #[account]
 #[derive(InitSpace)]
-pub struct Salon {
-    pub owner: Pubkey,
-    #[max_len(64)]
-    pub name: String,
-    pub is_active: bool,
-    pub total_earnings: u64,
-    pub reservation_count: u64,
-    #[max_len(10)]
-    pub services: Vec<Service>,
+pub struct SalonHours {
+    pub salon: Pubkey,
+    pub utc_offset_minutes: i16, // Local time = UTC + offset
+    pub weekly: [DayHours; 7],   // Monday first, local time
+    #[max_len(16)]
+    pub closures: Vec<Closure>,
     pub bump: u8,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
-pub struct Service {
-    pub id: u8,
-    #[max_len(32)]
-    pub name: String,
-    pub price_lamports: u64,
-    pub duration_minutes: u16,
-    pub is_active: bool,
+/// Opening window in minutes after local midnight; closed all day when open == close
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct DayHours {
+    pub open_minute: u16,
+    pub close_minute: u16,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct ServiceInput {
-    pub id: u8,
-    pub name: String,
-    pub price_lamports: u64,
-    pub duration_minutes: u16,
+/// Period [start, end) in Unix time during which the salon takes no bookings
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct Closure {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl SalonHours {
+    /// Reject appointments outside the local opening hours or overlapping a closure
+    pub fn check_open(&self, appointment_time: i64, duration_minutes: u16) -> Result<()> {
+        let end_time = math::add_secs(appointment_time, duration_minutes as i64 * 60)?;
+        require!(
+            !self
+                .closures
+                .iter()
+                .any(|closure| appointment_time < closure.end && end_time > closure.start),
+            GlamBookError::SalonClosed
+        );
+
+        let local_time = math::add_secs(appointment_time, self.utc_offset_minutes as i64 * 60)?;
+        let local_day = local_time.div_euclid(SECONDS_PER_DAY);
+        // 1970-01-01 was a Thursday (index 3 with Monday = 0)
+        let weekday = (local_day + 3).rem_euclid(7) as usize;
+        let hours = &self.weekly[weekday];
+
+        let start = local_time.rem_euclid(SECONDS_PER_DAY);
+        let end = start + duration_minutes as i64 * 60;
+        require!(
+            start >= hours.open_minute as i64 * 60 && end <= hours.close_minute as i64 * 60,
+            GlamBookError::OutsideBusinessHours
+        );
+
+        Ok(())
+    }
 }
 
 #[account]
 #[derive(InitSpace)]
-pub struct Reservation {
-    pub client: Pubkey,
-    pub salon: Pubkey,
-    pub salon_owner: Pubkey,
-    pub service_id: u8,
-    #[max_len(32)]
-    pub service_name: String,
-    pub amount: u64,
-    pub appointment_time: i64,
-    pub created_at: i64,
-    pub status: ReservationStatus,
-    pub cancelled_at: Option<i64>,
-    pub completed_at: Option<i64>,
+pub struct PaymentMintConfig {
+    pub mint: Pubkey,
+    pub units_per_eur: u64, // token base units per EUR, used for salon fees
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PriceConfig {
+    pub lamports_per_eur: u64,
+    pub updated_at: i64,
+    pub max_staleness_secs: i64,
+    pub oracle: Option<Pubkey>, // Pyth-format SOL/EUR price account
     pub bump: u8,
 }
 
@@ -604,6 +4654,10 @@ pub enum ReservationStatus {
     Cancelled,
     Completed,
     NoShow,
+    Rescheduled, // Escrow moved to a new reservation
+    CancelledBySalon,
+    Disputed,
+    DisputeResolved,
 }
 
 // ============== EVENTS ==============
@@ -614,11 +4668,194 @@ pub struct PlatformInitialized {
     pub treasury_wallet: Pubkey,
 }
 
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct TreasuryUpdated {
+    pub previous_treasury: Pubkey,
+    pub treasury_wallet: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub paused_operations: u8,
+}
+
+#[event]
+pub struct PlatformConfigUpdated {
+    pub commission_bps: u16,
+    pub no_show_grace_secs: i64,
+    pub default_cancellation_policy: CancellationPolicy,
+    pub settlement_window_secs: i64,
+    pub expired_settlement: ExpiredSettlement,
+    pub crank_reward_bps: u16,
+    pub dispute_window_secs: i64,
+    pub auto_close_reservations: bool,
+    pub tip_fee_bps: u16,
+}
+
+#[event]
+pub struct ArbiterUpdated {
+    pub arbiter: Pubkey,
+}
+
 #[event]
 pub struct SalonRegistered {
     pub salon: Pubkey,
     pub owner: Pubkey,
+    pub location_index: u16,
+    pub name: String,
+}
+
+#[event]
+pub struct ServiceAdded {
+    pub salon: Pubkey,
+    pub service_id: u8,
+    pub name: String,
+    pub price_lamports: u64,
+    pub duration_minutes: u16,
+}
+
+#[event]
+pub struct ServiceUpdated {
+    pub salon: Pubkey,
+    pub service_id: u8,
     pub name: String,
+    pub price_lamports: u64,
+    pub duration_minutes: u16,
+    pub is_active: bool,
+}
+
+#[event]
+pub struct ServiceRemoved {
+    pub salon: Pubkey,
+    pub service_id: u8,
+}
+
+#[event]
+pub struct CancellationPolicyUpdated {
+    pub salon: Pubkey,
+    pub policy: CancellationPolicy,
+}
+
+#[event]
+pub struct SalonCapacityUpdated {
+    pub salon: Pubkey,
+    pub chairs: u8,
+}
+
+#[event]
+pub struct ReschedulePolicyUpdated {
+    pub salon: Pubkey,
+    pub policy: ReschedulePolicy,
+}
+
+#[event]
+pub struct DepositPolicyUpdated {
+    pub salon: Pubkey,
+    pub deposit_bps: u16,
+}
+
+#[event]
+pub struct PayoutWalletUpdated {
+    pub salon: Pubkey,
+    pub payout_wallet: Pubkey,
+}
+
+#[event]
+pub struct DelegateAdded {
+    pub salon: Pubkey,
+    pub wallet: Pubkey,
+    pub role: SalonRole,
+    pub added_by: Pubkey,
+}
+
+#[event]
+pub struct DelegateRemoved {
+    pub salon: Pubkey,
+    pub wallet: Pubkey,
+    pub role: SalonRole,
+    pub removed_by: Pubkey,
+}
+
+#[event]
+pub struct StaffUpdated {
+    pub salon: Pubkey,
+    pub staff: Pubkey,
+    pub wallet: Pubkey,
+    pub service_ids: Vec<u8>,
+    pub commission_split_bps: u16,
+    pub capacity: u8,
+    pub is_active: bool,
+}
+
+#[event]
+pub struct StaffRemoved {
+    pub salon: Pubkey,
+    pub staff: Pubkey,
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct BondUpdated {
+    pub salon: Pubkey,
+    pub bond_lamports: u64,
+}
+
+#[event]
+pub struct BusinessHoursUpdated {
+    pub salon: Pubkey,
+    pub utc_offset_minutes: i16,
+    pub weekly: [DayHours; 7],
+}
+
+#[event]
+pub struct ClosureAdded {
+    pub salon: Pubkey,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[event]
+pub struct ClosureRemoved {
+    pub salon: Pubkey,
+    pub start: i64,
+    pub end: i64,
+}
+
+#[event]
+pub struct PaymentMintConfigured {
+    pub mint: Pubkey,
+    pub units_per_eur: u64,
+    pub is_active: bool,
+}
+
+#[event]
+pub struct PriceUpdated {
+    pub lamports_per_eur: u64,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct PriceOracleUpdated {
+    pub oracle: Option<Pubkey>,
+    pub max_staleness_secs: i64,
+}
+
+#[event]
+pub struct SalonPaymentMintUpdated {
+    pub salon: Pubkey,
+    pub payment_mint: Option<Pubkey>,
 }
 
 #[event]
@@ -626,9 +4863,12 @@ pub struct ReservationCreated {
     pub reservation: Pubkey,
     pub client: Pubkey,
     pub salon: Pubkey,
-    pub service_id: u8,
-    pub amount: u64,
+    pub service_ids: Vec<u8>,
+    pub amount: u64,      // Escrowed at booking
+    pub balance_due: u64, // Left to pay at the venue or via pay_balance
+    pub payment_mint: Option<Pubkey>,
     pub appointment_time: i64,
+    pub staff: Option<Pubkey>,
 }
 
 #[event]
@@ -641,6 +4881,24 @@ pub struct ReservationCancelled {
     pub time_until_appointment: i64,
 }
 
+#[event]
+pub struct LineItemRefunded {
+    pub reservation: Pubkey,
+    pub client: Pubkey,
+    pub index: u8,
+    pub service_id: u8,
+    pub refund_amount: u64,
+    pub refunded_by: Pubkey,
+}
+
+#[event]
+pub struct BalancePaid {
+    pub reservation: Pubkey,
+    pub client: Pubkey,
+    pub amount: u64,
+    pub total_held: u64, // Escrowed for the reservation afterwards
+}
+
 #[event]
 pub struct RefundProcessed {
     pub reservation: Pubkey,
@@ -648,19 +4906,111 @@ pub struct RefundProcessed {
     pub amount: u64,
 }
 
+#[event]
+pub struct ReservationRescheduled {
+    pub old_reservation: Pubkey,
+    pub new_reservation: Pubkey,
+    pub client: Pubkey,
+    pub old_appointment_time: i64,
+    pub new_appointment_time: i64,
+    pub salon_fee: u64,
+    pub app_commission: u64,
+}
+
+#[event]
+pub struct ReservationCancelledBySalon {
+    pub reservation: Pubkey,
+    pub salon: Pubkey,
+    pub client: Pubkey,
+    pub refund_amount: u64,
+    pub compensation: u64,
+}
+
+#[event]
+pub struct ReservationSettled {
+    pub reservation: Pubkey,
+    pub status: ReservationStatus,
+    pub salon_payment: u64,
+    pub staff_payment: u64,
+    pub app_commission: u64,
+    pub cranker: Pubkey,
+    pub crank_reward: u64,
+}
+
 #[event]
 pub struct ReservationCompleted {
     pub reservation: Pubkey,
     pub salon_payment: u64,
+    pub staff_payment: u64,
+    pub app_commission: u64,
+    pub payout_held_until: Option<i64>,
+}
+
+#[event]
+pub struct ReservationPartiallyCompleted {
+    pub reservation: Pubkey,
+    pub amount_to_salon: u64,
+    pub client_refund: u64,
+    pub salon_payment: u64,
+    pub staff_payment: u64,
+    pub app_commission: u64,
+    pub payout_held_until: Option<i64>,
+}
+
+#[event]
+pub struct ServiceConfirmed {
+    pub reservation: Pubkey,
+    pub client: Pubkey,
+    pub salon_payment: u64,
+    pub staff_payment: u64,
     pub app_commission: u64,
 }
 
+#[event]
+pub struct TipPaid {
+    pub reservation: Pubkey,
+    pub client: Pubkey,
+    pub recipient: Pubkey, // Stylist wallet, or the salon payout wallet without one
+    pub amount: u64,       // Received by the recipient
+    pub tip_fee: u64,
+}
+
 #[event]
 pub struct ReservationNoShow {
     pub reservation: Pubkey,
     pub client: Pubkey,
     pub salon_payment: u64,
     pub app_commission: u64,
+    pub payout_held_until: Option<i64>,
+}
+
+#[event]
+pub struct ReservationClosed {
+    pub reservation: Pubkey,
+    pub client: Pubkey,
+    pub salon: Pubkey,
+    pub status: ReservationStatus,
+    pub amount: u64,
+    pub appointment_time: i64,
+    pub rent_returned: u64,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub reservation: Pubkey,
+    pub client: Pubkey,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub reservation: Pubkey,
+    pub arbiter: Pubkey,
+    pub client_bps: u16,
+    pub client_refund: u64,
+    pub salon_payment: u64,
+    pub staff_payment: u64,
+    pub app_commission: u64,
 }
 
 // ============== ERRORS ==============
@@ -691,6 +5041,138 @@ pub enum GlamBookError {
     UnauthorizedNoShow,
     #[msg("Too early to mark as no-show")]
     TooEarlyForNoShow,
+    #[msg("Service name exceeds maximum length of 32 characters")]
+    ServiceNameTooLong,
+    #[msg("A service with this id already exists")]
+    DuplicateServiceId,
+    #[msg("Only the platform admin can perform this action")]
+    UnauthorizedAdmin,
+    #[msg("Conversion rate must be greater than zero")]
+    InvalidConversionRate,
+    #[msg("Payment mint is not accepted by the platform")]
+    PaymentMintNotAccepted,
+    #[msg("Payment mint does not match the reservation")]
+    InvalidPaymentMint,
+    #[msg("Token accounts are required for SPL token payments")]
+    MissingTokenAccounts,
+    #[msg("Token account has the wrong owner or mint")]
+    InvalidTokenAccount,
+    #[msg("Staleness window must be greater than zero")]
+    InvalidStalenessWindow,
+    #[msg("SOL/EUR price is stale")]
+    StalePrice,
+    #[msg("Price feed account is required")]
+    MissingPriceFeed,
+    #[msg("Invalid price feed account")]
+    InvalidPriceFeed,
+    #[msg("Cancellation tiers must be 1-5, sorted by notice and never favour later cancellation")]
+    InvalidCancellationPolicy,
+    #[msg("Commission exceeds the maximum of 20%")]
+    CommissionTooHigh,
+    #[msg("Platform configuration value out of bounds")]
+    InvalidPlatformConfig,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("This operation is paused by the platform admin")]
+    OperationPaused,
+    #[msg("Chair count must be at least 1")]
+    InvalidChairCount,
+    #[msg("No chair available for the requested time")]
+    SlotUnavailable,
+    #[msg("Appointment must end on the salon-local day it starts")]
+    AppointmentCrossesDay,
+    #[msg("Schedule account does not cover the appointment")]
+    InvalidSchedule,
+    #[msg("Opening hours must be within the day and open before close")]
+    InvalidBusinessHours,
+    #[msg("Appointment is outside the salon's business hours")]
+    OutsideBusinessHours,
+    #[msg("Salon is closed during the requested time")]
+    SalonClosed,
+    #[msg("Salon business hours account is required")]
+    MissingBusinessHours,
+    #[msg("Closure must end after it starts")]
+    InvalidClosure,
+    #[msg("Maximum 16 upcoming closures allowed per salon")]
+    TooManyClosures,
+    #[msg("Closure not found")]
+    ClosureNotFound,
+    #[msg("Reschedule policy notice must be non-negative and fee at most 10000 bps")]
+    InvalidReschedulePolicy,
+    #[msg("Too close to the appointment to reschedule")]
+    RescheduleWindowClosed,
+    #[msg("Salon owner must approve this reschedule")]
+    SalonApprovalRequired,
+    #[msg("Only the client can reschedule their reservation")]
+    UnauthorizedReschedule,
+    #[msg("Salon does not match the reservation")]
+    InvalidSalon,
+    #[msg("Salon bond is too small")]
+    InsufficientBond,
+    #[msg("Only the salon owner can cancel on the salon's behalf")]
+    UnauthorizedSalonCancellation,
+    #[msg("Client does not match the reservation")]
+    InvalidClient,
+    #[msg("Reservation can only be settled after the settlement window")]
+    SettlementWindowOpen,
+    #[msg("Payout is held until the dispute window ends")]
+    DisputeWindowOpen,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Only the client can dispute their reservation")]
+    UnauthorizedDispute,
+    #[msg("Only the platform arbiter can resolve disputes")]
+    UnauthorizedArbiter,
+    #[msg("Client share cannot exceed 10000 bps")]
+    InvalidDisputeSplit,
+    #[msg("Reservation still holds funds or can change state")]
+    ReservationNotSettled,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Payout split does not add up to the escrowed amount")]
+    InvalidPayoutSplit,
+    #[msg("Escrow balance cannot cover the payout and stay rent-exempt")]
+    InsufficientEscrow,
+    #[msg("Staff member does not match the salon or reservation")]
+    InvalidStaff,
+    #[msg("Staff member is not taking bookings")]
+    StaffInactive,
+    #[msg("Staff member does not perform this service")]
+    StaffServiceNotOffered,
+    #[msg("Staff split cannot exceed 10000 bps")]
+    InvalidStaffSplit,
+    #[msg("Delegates can only be managers or receptionists")]
+    InvalidSalonRole,
+    #[msg("Signer cannot add or remove delegates with this role")]
+    UnauthorizedDelegateChange,
+    #[msg("Wallet already has a role at this salon")]
+    DuplicateDelegate,
+    #[msg("Maximum 8 delegates allowed per salon")]
+    TooManyDelegates,
+    #[msg("Delegate not found")]
+    DelegateNotFound,
+    #[msg("Payout wallet does not match the salon")]
+    InvalidPayoutWallet,
+    #[msg("A reservation needs 1-5 services and keeps at least one")]
+    InvalidLineItems,
+    #[msg("Line item not found or already refunded")]
+    LineItemNotFound,
+    #[msg("Only the salon owner or a manager can refund line items")]
+    UnauthorizedLineItemRefund,
+    #[msg("Salon amount must be above zero and at most the reservation amount")]
+    InvalidPartialAmount,
+    #[msg("Tip amount must be above zero")]
+    InvalidTipAmount,
+    #[msg("Deposit must be between 1 and 10000 basis points")]
+    InvalidDeposit,
+    #[msg("Reservation has no balance left to pay")]
+    NoBalanceDue,
+    #[msg("Price config account is required for a cancellation fee")]
+    MissingPriceConfig,
+    #[msg("Changing the payment mint needs a new price for every service")]
+    InvalidServicePrices,
+    #[msg("Only the salon owner or a manager can complete for part of the price")]
+    UnauthorizedPartialCompletion,
+    #[msg("Tip would leave a wallet that is still empty below rent exemption")]
+    TipBelowRentExemption,
 }
-
-
@@ -0,0 +1,83 @@
+//! Checked arithmetic for escrowed amounts, counters and timestamps.
+//! Overflows fail the instruction with `MathOverflow` instead of wrapping or panicking.
+
+use anchor_lang::prelude::*;
+
+use crate::{GlamBookError, BPS_DENOMINATOR};
+
+pub fn add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| error!(GlamBookError::MathOverflow))
+}
+
+pub fn sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or_else(|| error!(GlamBookError::MathOverflow))
+}
+
+/// `amount * numerator / denominator`, rounded down, with a u128 intermediate
+pub fn mul_div(amount: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, GlamBookError::MathOverflow);
+    let value = amount as u128 * numerator as u128 / denominator as u128;
+    u64::try_from(value).map_err(|_| error!(GlamBookError::MathOverflow))
+}
+
+/// Share of `amount` in basis points, rounded down
+pub fn bps(amount: u64, bps: u16) -> Result<u64> {
+    mul_div(amount, bps as u64, BPS_DENOMINATOR)
+}
+
+/// Unix timestamp `secs` seconds after `time`
+pub fn add_secs(time: i64, secs: i64) -> Result<i64> {
+    time.checked_add(secs).ok_or_else(|| error!(GlamBookError::MathOverflow))
+}
+
+/// Seconds from `earlier` to `time`; negative when `time` comes first
+pub fn sub_secs(time: i64, earlier: i64) -> Result<i64> {
+    time.checked_sub(earlier).ok_or_else(|| error!(GlamBookError::MathOverflow))
+}
+
+/// Require `balance` to keep at least `rent_exempt_minimum` after paying out `amount`
+pub fn require_rent_floor(balance: u64, amount: u64, rent_exempt_minimum: u64) -> Result<()> {
+    require!(
+        balance >= add(amount, rent_exempt_minimum)?,
+        GlamBookError::InsufficientEscrow
+    );
+    Ok(())
+}
+
+/// Require a payout split to account for exactly the escrowed amount
+pub fn require_split(escrowed: u64, parts: &[u64]) -> Result<()> {
+    let total = parts.iter().try_fold(0u64, |total, part| add(total, *part))?;
+    require!(total == escrowed, GlamBookError::InvalidPayoutSplit);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rent_floor_allows_payouts_above_rent() {
+        assert!(require_rent_floor(150, 50, 100).is_ok());
+        assert!(require_rent_floor(150, 0, 100).is_ok());
+    }
+
+    #[test]
+    fn rent_floor_rejects_payouts_into_rent() {
+        assert_eq!(
+            require_rent_floor(150, 51, 100).unwrap_err(),
+            error!(GlamBookError::InsufficientEscrow)
+        );
+        assert_eq!(
+            require_rent_floor(50, 0, 100).unwrap_err(),
+            error!(GlamBookError::InsufficientEscrow)
+        );
+    }
+
+    #[test]
+    fn rent_floor_rejects_overflowing_payouts() {
+        assert_eq!(
+            require_rent_floor(u64::MAX, u64::MAX, 1).unwrap_err(),
+            error!(GlamBookError::MathOverflow)
+        );
+    }
+}
@@ -75,11 +75,43 @@ async function main() {
     console.error("❌ Error initializing platform:", error);
   }
 
+  // Initialize SOL/EUR price used for cancellation fees
+  const [priceConfigPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("price_config")],
+    programId
+  );
+
+  try {
+    const priceConfigAccount = await provider.connection.getAccountInfo(priceConfigPDA);
+
+    if (priceConfigAccount) {
+      console.log("ℹ️  Price config already initialized");
+    } else {
+      const tx = await program.methods
+        .initializePriceConfig(
+          new anchor.BN(50_000_000), // 0.05 SOL per EUR, update with update_price or set_price_oracle
+          new anchor.BN(24 * 60 * 60)
+        )
+        .accounts({
+          platform: platformPDA,
+          priceConfig: priceConfigPDA,
+          admin: provider.wallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+
+      console.log("✅ Price config initialized! TX:", tx);
+    }
+  } catch (error) {
+    console.error("❌ Error initializing price config:", error);
+  }
+
   // Save deployment info
   const deploymentInfo = {
     network: "devnet",
     programId: programId.toBase58(),
     platformPDA: platformPDA.toBase58(),
+    priceConfigPDA: priceConfigPDA.toBase58(),
     treasuryWallet: treasuryWallet.publicKey.toBase58(),
     admin: provider.wallet.publicKey.toBase58(),
     deployedAt: new Date().toISOString(),
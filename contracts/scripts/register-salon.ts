@@ -21,9 +21,27 @@ async function main() {
   const programId = new PublicKey(idl.metadata.address);
   const program = new anchor.Program(idl, programId, provider);
 
-  // Derive salon PDA
+  // Derive platform and salon PDAs
+  const [platformPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("platform")],
+    programId
+  );
+  const [ownerRegistryPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("owner"), provider.wallet.publicKey.toBuffer()],
+    programId
+  );
+
+  // New locations take the next index of the owner's registry
+  const ownerRegistry = await provider.connection.getAccountInfo(ownerRegistryPDA);
+  const locationIndex = ownerRegistry
+    ? (await program.account.owner.fetch(ownerRegistryPDA)).locationCount
+    : 0;
   const [salonPDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from("salon"), provider.wallet.publicKey.toBuffer()],
+    [
+      Buffer.from("salon"),
+      provider.wallet.publicKey.toBuffer(),
+      new anchor.BN(locationIndex).toArrayLike(Buffer, "le", 2),
+    ],
     programId
   );
 
@@ -61,6 +79,8 @@ async function main() {
     const tx = await program.methods
       .registerSalon("Studio Hair Demo", services)
       .accounts({
+        platform: platformPDA,
+        ownerRegistry: ownerRegistryPDA,
         salon: salonPDA,
         owner: provider.wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
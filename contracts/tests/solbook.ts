@@ -2,6 +2,13 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Solbook } from "../target/types/solbook";
 import { PublicKey, Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
 import { expect } from "chai";
 
 describe("solbook", () => {
@@ -14,18 +21,46 @@ describe("solbook", () => {
   const treasuryWallet = Keypair.generate();
   const salonOwner = Keypair.generate();
   const client = Keypair.generate();
+  const tokenSalonOwner = Keypair.generate();
+  const otherClient = Keypair.generate();
+  const hoursSalonOwner = Keypair.generate();
 
   let platformPDA: PublicKey;
+  let priceConfigPDA: PublicKey;
   let salonPDA: PublicKey;
   let reservationPDA: PublicKey;
 
   const appointmentTime = Math.floor(Date.now() / 1000) + 72 * 60 * 60; // 72 hours from now
 
+  // Per-day booking schedule of a salon (local days at the salon's UTC offset)
+  const schedulePDA = (resource: PublicKey, time: number, utcOffsetMinutes = 0) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("schedule"),
+        resource.toBuffer(),
+        new anchor.BN(Math.floor((time + utcOffsetMinutes * 60) / 86400)).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  // Location index seed of an owner's salon (u16, little-endian)
+  const locationSeed = (index: number) => new anchor.BN(index).toArrayLike(Buffer, "le", 2);
+
+  // Registry counting the salon locations of an owner
+  const ownerRegistryPDA = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from("owner"), owner.toBuffer()], program.programId)[0];
+
+  // Pyth-format SOL/EUR price account loaded by the test validator (see Anchor.toml)
+  const mockPriceFeed = new PublicKey("FP4AS3rPtLqjY3rVEfsESqnrZeXcc1ydVLvA943ZQgh6");
+
   before(async () => {
     // Airdrop SOL to test accounts
     await provider.connection.requestAirdrop(admin.publicKey, 10 * LAMPORTS_PER_SOL);
     await provider.connection.requestAirdrop(salonOwner.publicKey, 10 * LAMPORTS_PER_SOL);
     await provider.connection.requestAirdrop(client.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.requestAirdrop(tokenSalonOwner.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.requestAirdrop(otherClient.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.requestAirdrop(hoursSalonOwner.publicKey, 10 * LAMPORTS_PER_SOL);
     
     // Wait for confirmations
     await new Promise(resolve => setTimeout(resolve, 2000));
@@ -36,8 +71,13 @@ describe("solbook", () => {
       program.programId
     );
 
+    [priceConfigPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("price_config")],
+      program.programId
+    );
+
     [salonPDA] = PublicKey.findProgramAddressSync(
-      [Buffer.from("salon"), salonOwner.publicKey.toBuffer()],
+      [Buffer.from("salon"), salonOwner.publicKey.toBuffer(), locationSeed(0)],
       program.programId
     );
   });
@@ -59,6 +99,169 @@ describe("solbook", () => {
     expect(platform.totalReservations.toNumber()).to.equal(0);
   });
 
+  it("Updates the platform config within bounds", async () => {
+    await program.methods
+      .updatePlatformConfig({
+        commissionBps: 500,
+        noShowGraceSecs: new anchor.BN(30 * 60),
+        defaultCancellationPolicy: null,
+        settlementWindowSecs: null,
+        expiredSettlement: null,
+        crankRewardBps: null,
+        disputeWindowSecs: null,
+        autoCloseReservations: null,
+        tipFeeBps: null,
+      })
+      .accounts({ platform: platformPDA, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    let platform = await program.account.platform.fetch(platformPDA);
+    expect(platform.commissionBps).to.equal(500);
+    expect(platform.noShowGraceSecs.toNumber()).to.equal(30 * 60);
+
+    let rejected = false;
+    try {
+      await program.methods
+        .updatePlatformConfig({
+          commissionBps: 2500,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: null,
+          expiredSettlement: null,
+          crankRewardBps: null,
+          disputeWindowSecs: null,
+          autoCloseReservations: null,
+          tipFeeBps: null,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("CommissionTooHigh");
+    }
+    expect(rejected).to.be.true;
+
+    // Restore defaults for the remaining tests
+    await program.methods
+      .updatePlatformConfig({
+        commissionBps: 300,
+        noShowGraceSecs: new anchor.BN(15 * 60),
+        defaultCancellationPolicy: null,
+        settlementWindowSecs: null,
+        expiredSettlement: null,
+        crankRewardBps: null,
+        disputeWindowSecs: null,
+        autoCloseReservations: null,
+        tipFeeBps: null,
+      })
+      .accounts({ platform: platformPDA, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    platform = await program.account.platform.fetch(platformPDA);
+    expect(platform.commissionBps).to.equal(300);
+  });
+
+  it("Hands over admin in two steps and rotates the treasury", async () => {
+    const newAdmin = Keypair.generate();
+    const propose = (key: PublicKey) =>
+      program.methods
+        .proposeAdmin(key)
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    // Proposing the default key withdraws a pending proposal
+    await propose(client.publicKey);
+    await propose(PublicKey.default);
+    let platform = await program.account.platform.fetch(platformPDA);
+    expect(platform.pendingAdmin).to.be.null;
+
+    await propose(newAdmin.publicKey);
+    platform = await program.account.platform.fetch(platformPDA);
+    expect(platform.pendingAdmin.toString()).to.equal(newAdmin.publicKey.toString());
+
+    // Only the proposed key can accept
+    let rejected = false;
+    try {
+      await program.methods
+        .acceptAdmin()
+        .accounts({ platform: platformPDA, newAdmin: client.publicKey })
+        .signers([client])
+        .rpc();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("NotPendingAdmin");
+    }
+    expect(rejected).to.be.true;
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({ platform: platformPDA, newAdmin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+
+    platform = await program.account.platform.fetch(platformPDA);
+    expect(platform.admin.toString()).to.equal(newAdmin.publicKey.toString());
+    expect(platform.pendingAdmin).to.be.null;
+
+    const newTreasury = Keypair.generate();
+    await program.methods
+      .setTreasury(newTreasury.publicKey)
+      .accounts({ platform: platformPDA, admin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+
+    platform = await program.account.platform.fetch(platformPDA);
+    expect(platform.treasuryWallet.toString()).to.equal(newTreasury.publicKey.toString());
+
+    // Hand everything back so the remaining tests keep their keys
+    await program.methods
+      .setTreasury(treasuryWallet.publicKey)
+      .accounts({ platform: platformPDA, admin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+    await program.methods
+      .proposeAdmin(admin.publicKey)
+      .accounts({ platform: platformPDA, admin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+    await program.methods
+      .acceptAdmin()
+      .accounts({ platform: platformPDA, newAdmin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+  });
+
+  it("Initializes the SOL/EUR price config", async () => {
+    await program.methods
+      .initializePriceConfig(new anchor.BN(50_000_000), new anchor.BN(3600))
+      .accounts({
+        platform: platformPDA,
+        priceConfig: priceConfigPDA,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    await program.methods
+      .updatePrice(new anchor.BN(6_000_000))
+      .accounts({
+        platform: platformPDA,
+        priceConfig: priceConfigPDA,
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
+    const priceConfig = await program.account.priceConfig.fetch(priceConfigPDA);
+    expect(priceConfig.lamportsPerEur.toNumber()).to.equal(6_000_000);
+    expect(priceConfig.oracle).to.be.null;
+  });
+
   it("Registers a salon", async () => {
     const services = [
       {
@@ -74,22 +277,103 @@ describe("solbook", () => {
         durationMinutes: 45,
       },
     ];
+    const register = (services: any[]) =>
+      program.methods
+        .registerSalon("Beauty Studio", services)
+        .accounts({
+          platform: platformPDA,
+          ownerRegistry: ownerRegistryPDA(salonOwner.publicKey),
+          salon: salonPDA,
+          owner: salonOwner.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([salonOwner])
+        .rpc();
 
+    let rejected = false;
+    try {
+      await register([services[0], { ...services[1], id: 1 }]);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("DuplicateServiceId");
+    }
+    expect(rejected).to.be.true;
+
+    await register(services);
+
+    const salon = await program.account.salon.fetch(salonPDA);
+    expect(salon.name).to.equal("Beauty Studio");
+    expect(salon.owner.toString()).to.equal(salonOwner.publicKey.toString());
+    expect(salon.services.length).to.equal(2);
+    expect(salon.isActive).to.be.true;
+  });
+
+  it("Adds, updates and removes a service", async () => {
     await program.methods
-      .registerSalon("Beauty Studio", services)
+      .addService({
+        id: 3,
+        name: "Blow-dry",
+        priceLamports: new anchor.BN(0.05 * LAMPORTS_PER_SOL),
+        durationMinutes: 20,
+      })
       .accounts({
         salon: salonPDA,
         owner: salonOwner.publicKey,
-        systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([salonOwner])
       .rpc();
 
-    const salon = await program.account.salon.fetch(salonPDA);
-    expect(salon.name).to.equal("Beauty Studio");
-    expect(salon.owner.toString()).to.equal(salonOwner.publicKey.toString());
+    let salon = await program.account.salon.fetch(salonPDA);
+    expect(salon.services.length).to.equal(3);
+
+    await program.methods
+      .updateService(3, {
+        name: null,
+        priceLamports: new anchor.BN(0.06 * LAMPORTS_PER_SOL),
+        durationMinutes: 25,
+        isActive: false,
+      })
+      .accounts({
+        salon: salonPDA,
+        owner: salonOwner.publicKey,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    salon = await program.account.salon.fetch(salonPDA);
+    const updated = salon.services.find((s: any) => s.id === 3);
+    expect(updated.priceLamports.toNumber()).to.equal(0.06 * LAMPORTS_PER_SOL);
+    expect(updated.durationMinutes).to.equal(25);
+    expect(updated.isActive).to.be.false;
+
+    await program.methods
+      .removeService(3)
+      .accounts({
+        salon: salonPDA,
+        owner: salonOwner.publicKey,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    salon = await program.account.salon.fetch(salonPDA);
     expect(salon.services.length).to.equal(2);
-    expect(salon.isActive).to.be.true;
+  });
+
+  it("Rejects service changes from a non-owner", async () => {
+    let rejected = false;
+    try {
+      await program.methods
+        .removeService(1)
+        .accounts({
+          salon: salonPDA,
+          owner: client.publicKey,
+        })
+        .signers([client])
+        .rpc();
+    } catch (err: any) {
+      rejected = true;
+    }
+    expect(rejected).to.be.true;
   });
 
   it("Creates a reservation", async () => {
@@ -108,11 +392,12 @@ describe("solbook", () => {
     const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
 
     await program.methods
-      .createReservation(serviceId, new anchor.BN(appointmentTime))
+      .createReservation(Buffer.from([serviceId]), new anchor.BN(appointmentTime))
       .accounts({
         platform: platformPDA,
         salon: salonPDA,
         reservation: reservationPDA,
+        schedule: schedulePDA(salonPDA, appointmentTime),
         client: client.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -121,7 +406,8 @@ describe("solbook", () => {
 
     const reservation = await program.account.reservation.fetch(reservationPDA);
     expect(reservation.client.toString()).to.equal(client.publicKey.toString());
-    expect(reservation.serviceId).to.equal(serviceId);
+    expect(reservation.lineItems.length).to.equal(1);
+    expect(reservation.lineItems[0].serviceId).to.equal(serviceId);
     expect(reservation.status.confirmed).to.not.be.undefined;
 
     const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
@@ -136,10 +422,13 @@ describe("solbook", () => {
       .cancelReservation()
       .accounts({
         reservation: reservationPDA,
+        schedule: schedulePDA(salonPDA, appointmentTime),
         client: client.publicKey,
-        salonOwner: salonOwner.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
         platform: platformPDA,
         treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([client])
@@ -168,11 +457,12 @@ describe("solbook", () => {
     );
 
     await program.methods
-      .createReservation(serviceId, new anchor.BN(newAppointmentTime))
+      .createReservation(Buffer.from([serviceId]), new anchor.BN(newAppointmentTime))
       .accounts({
         platform: platformPDA,
         salon: salonPDA,
         reservation: newReservationPDA,
+        schedule: schedulePDA(salonPDA, newAppointmentTime),
         client: client.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -188,7 +478,8 @@ describe("solbook", () => {
       .accounts({
         reservation: newReservationPDA,
         salon: salonPDA,
-        salonOwner: salonOwner.publicKey,
+        operator: salonOwner.publicKey,
+        payoutWallet: salonOwner.publicKey,
         platform: platformPDA,
         treasury: treasuryWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
@@ -199,9 +490,2335 @@ describe("solbook", () => {
     const reservation = await program.account.reservation.fetch(newReservationPDA);
     expect(reservation.status.completed).to.not.be.undefined;
 
-    const salon = await program.account.salon.fetch(salonPDA);
-    expect(salon.totalEarnings.toNumber()).to.be.greaterThan(0);
+    // Payout stays in escrow for the dispute window (48h by default)
+    expect(reservation.payoutHeldUntil.toNumber()).to.be.greaterThan(newAppointmentTime);
+    const escrowBalance = await provider.connection.getBalance(newReservationPDA);
+    expect(escrowBalance).to.be.greaterThan(0.15 * LAMPORTS_PER_SOL);
   });
-});
 
+  it("Pays for a reservation in an SPL token and refunds on cancellation", async () => {
+    const mint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+    const [paymentMintConfigPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_mint"), mint.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .addPaymentMint(new anchor.BN(1_000_000)) // 1 token = 1 EUR
+      .accounts({
+        platform: platformPDA,
+        paymentMintConfig: paymentMintConfigPDA,
+        mint,
+        admin: admin.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    const [tokenSalonPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("salon"), tokenSalonOwner.publicKey.toBuffer(), locationSeed(0)],
+      program.programId
+    );
+
+    await program.methods
+      .registerSalon("Token Studio", [
+        { id: 1, name: "Colouring", priceLamports: new anchor.BN(40_000_000), durationMinutes: 90 },
+      ])
+      .accounts({
+        platform: platformPDA,
+        ownerRegistry: ownerRegistryPDA(tokenSalonOwner.publicKey),
+        salon: tokenSalonPDA,
+        owner: tokenSalonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([tokenSalonOwner])
+      .rpc();
+
+    const setPaymentMint = (prices: { serviceId: number; price: anchor.BN }[]) =>
+      program.methods
+        .setPaymentMint(prices)
+        .accounts({
+          salon: tokenSalonPDA,
+          paymentMintConfig: paymentMintConfigPDA,
+          owner: tokenSalonOwner.publicKey,
+        })
+        .signers([tokenSalonOwner])
+        .rpc();
+
+    // Prices are in base units of the mint, so the switch must re-price every service
+    let rejected = false;
+    try {
+      await setPaymentMint([]);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidServicePrices");
+    }
+    expect(rejected).to.be.true;
 
+    await setPaymentMint([{ serviceId: 1, price: new anchor.BN(40_000_000) }]); // 40 tokens
+
+    const clientAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection, client, mint, client.publicKey
+    );
+    const salonAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection, tokenSalonOwner, mint, tokenSalonOwner.publicKey
+    );
+    const treasuryAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection, admin, mint, treasuryWallet.publicKey
+    );
+    await mintTo(provider.connection, admin, mint, clientAta.address, admin, 100_000_000);
+
+    const tokenAppointmentTime = appointmentTime + 3600;
+    const [tokenReservationPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        tokenSalonPDA.toBuffer(),
+        new anchor.BN(tokenAppointmentTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrowPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), tokenReservationPDA.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(tokenAppointmentTime))
+      .accounts({
+        platform: platformPDA,
+        salon: tokenSalonPDA,
+        reservation: tokenReservationPDA,
+        schedule: schedulePDA(tokenSalonPDA, tokenAppointmentTime),
+        client: client.publicKey,
+        paymentMint: mint,
+        clientTokenAccount: clientAta.address,
+        escrowTokenAccount: escrowPDA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const reservation = await program.account.reservation.fetch(tokenReservationPDA);
+    expect(reservation.paymentMint.toString()).to.equal(mint.toString());
+    expect(Number((await getAccount(provider.connection, escrowPDA)).amount)).to.equal(40_000_000);
+
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: tokenReservationPDA,
+        schedule: schedulePDA(tokenSalonPDA, tokenAppointmentTime),
+        client: client.publicKey,
+        salon: tokenSalonPDA,
+        payoutWallet: tokenSalonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        escrowTokenAccount: escrowPDA,
+        clientTokenAccount: clientAta.address,
+        paymentMintConfig: paymentMintConfigPDA,
+        priceConfig: priceConfigPDA,
+        salonTokenAccount: salonAta.address,
+        treasuryTokenAccount: treasuryAta.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // > 48h before the appointment: full refund in tokens
+    expect(Number((await getAccount(provider.connection, clientAta.address)).amount)).to.equal(100_000_000);
+    expect(Number((await getAccount(provider.connection, escrowPDA)).amount)).to.equal(0);
+  });
+
+  it("Converts the late cancellation fee with the Pyth-format price feed", async () => {
+    await program.methods
+      .setPriceOracle(mockPriceFeed, new anchor.BN(10 * 365 * 24 * 60 * 60))
+      .accounts({
+        platform: platformPDA,
+        priceConfig: priceConfigPDA,
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+
+    // 30 hours ahead: 80% refund and a €2 salon fee
+    const lateAppointmentTime = Math.floor(Date.now() / 1000) + 30 * 60 * 60;
+    const [lateReservationPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(lateAppointmentTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(lateAppointmentTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: lateReservationPDA,
+        schedule: schedulePDA(salonPDA, lateAppointmentTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: lateReservationPDA,
+        schedule: schedulePDA(salonPDA, lateAppointmentTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        priceFeed: mockPriceFeed,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // Feed quotes 1 SOL = 150 EUR, so €2 = 13_333_332 lamports, minus 3% commission
+    const salonFee = 2 * Math.floor(LAMPORTS_PER_SOL / 150);
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    expect(salonBalanceAfter - salonBalanceBefore).to.equal(salonFee - Math.floor((salonFee * 300) / 10000));
+
+    await program.methods
+      .setPriceOracle(null, new anchor.BN(3600))
+      .accounts({
+        platform: platformPDA,
+        priceConfig: priceConfigPDA,
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+  });
+
+  it("Snapshots the salon cancellation policy at booking time", async () => {
+    const hours = (h: number) => new anchor.BN(h * 60 * 60);
+    const freeCancellation72h = {
+      tiers: [
+        { minNoticeSecs: hours(72), refundBps: 10000, feeEurCents: new anchor.BN(0) },
+        { minNoticeSecs: hours(0), refundBps: 5000, feeEurCents: new anchor.BN(0) },
+      ],
+      lateFeeEurCents: new anchor.BN(1000),
+    };
+
+    // Tiers must be sorted by descending notice
+    let rejected = false;
+    try {
+      await program.methods
+        .setCancellationPolicy({ ...freeCancellation72h, tiers: [...freeCancellation72h.tiers].reverse() })
+        .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+        .signers([salonOwner])
+        .rpc();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidCancellationPolicy");
+    }
+    expect(rejected).to.be.true;
+
+    await program.methods
+      .setCancellationPolicy(freeCancellation72h)
+      .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+
+    const policyAppointmentTime = Math.floor(Date.now() / 1000) + 60 * 60 * 60;
+    const [policyReservationPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(policyAppointmentTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(policyAppointmentTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: policyReservationPDA,
+        schedule: schedulePDA(salonPDA, policyAppointmentTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // Going back to the default policy must not affect the booking above
+    const defaultPolicy = {
+      tiers: [
+        { minNoticeSecs: hours(48), refundBps: 10000, feeEurCents: new anchor.BN(0) },
+        { minNoticeSecs: hours(24), refundBps: 8000, feeEurCents: new anchor.BN(200) },
+        { minNoticeSecs: hours(0), refundBps: 5000, feeEurCents: new anchor.BN(500) },
+      ],
+      lateFeeEurCents: new anchor.BN(1000),
+    };
+    await program.methods
+      .setCancellationPolicy(defaultPolicy)
+      .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+
+    const reservation = await program.account.reservation.fetch(policyReservationPDA);
+    expect(reservation.cancellationPolicy.tiers.length).to.equal(2);
+
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: policyReservationPDA,
+        schedule: schedulePDA(salonPDA, policyAppointmentTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // 60h ahead falls in the snapshotted 0-72h tier: 50% refund
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceAfter - clientBalanceBefore).to.equal(0.05 * LAMPORTS_PER_SOL);
+  });
+
+  it("Blocks bookings while paused but still lets clients cancel", async () => {
+    const reservationAt = (time: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("reservation"),
+          client.publicKey.toBuffer(),
+          salonPDA.toBuffer(),
+          new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const bookedTime = appointmentTime + 2 * 3600;
+    const blockedTime = appointmentTime + 4 * 3600;
+
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(bookedTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: reservationAt(bookedTime),
+        schedule: schedulePDA(salonPDA, bookedTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const PAUSE_ALL = 0b111;
+    await program.methods
+      .setPausedOperations(PAUSE_ALL)
+      .accounts({ platform: platformPDA, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+
+    let rejected = false;
+    try {
+      await program.methods
+        .createReservation(Buffer.from([1]), new anchor.BN(blockedTime))
+        .accounts({
+          platform: platformPDA,
+          salon: salonPDA,
+          reservation: reservationAt(blockedTime),
+          schedule: schedulePDA(salonPDA, blockedTime),
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([client])
+        .rpc();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("OperationPaused");
+    }
+    expect(rejected).to.be.true;
+
+    // Bond withdrawals are payouts too
+    rejected = false;
+    try {
+      await program.methods
+        .withdrawBond(new anchor.BN(0))
+        .accounts({
+          platform: platformPDA,
+          salon: salonPDA,
+          owner: salonOwner.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([salonOwner])
+        .rpc();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("OperationPaused");
+    }
+    expect(rejected).to.be.true;
+
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: reservationAt(bookedTime),
+        schedule: schedulePDA(salonPDA, bookedTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const reservation = await program.account.reservation.fetch(reservationAt(bookedTime));
+    expect(reservation.status.cancelled).to.not.be.undefined;
+
+    await program.methods
+      .setPausedOperations(0)
+      .accounts({ platform: platformPDA, admin: admin.publicKey })
+      .signers([admin])
+      .rpc();
+  });
+
+  it("Rejects overlapping bookings once every chair is taken", async () => {
+    // Noon UTC, two days after the other bookings
+    const slotTime = (Math.floor(appointmentTime / 86400) + 2) * 86400 + 12 * 3600;
+    const reservationFor = (who: PublicKey, time: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("reservation"),
+          who.toBuffer(),
+          salonPDA.toBuffer(),
+          new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const book = (who: Keypair, time: number) =>
+      program.methods
+        .createReservation(Buffer.from([2]), new anchor.BN(time)) // 45 minute service
+        .accounts({
+          platform: platformPDA,
+          salon: salonPDA,
+          reservation: reservationFor(who.publicKey, time),
+          schedule: schedulePDA(salonPDA, time),
+          client: who.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([who])
+        .rpc();
+
+    await book(client, slotTime);
+
+    // Starts 30 minutes later, still inside the first 45 minute appointment
+    let rejected = false;
+    try {
+      await book(otherClient, slotTime + 30 * 60);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("SlotUnavailable");
+    }
+    expect(rejected).to.be.true;
+
+    // A second chair makes room for the overlapping booking
+    await program.methods
+      .setChairs(2)
+      .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+    await book(otherClient, slotTime + 30 * 60);
+
+    const schedule = await program.account.daySchedule.fetch(schedulePDA(salonPDA, slotTime));
+    const firstSlot = Math.floor((slotTime % 86400) / 900);
+    expect(schedule.slots[firstSlot]).to.equal(1);
+    expect(schedule.slots[firstSlot + 2]).to.equal(2);
+
+    // Cancelling frees the chair again
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: reservationFor(client.publicKey, slotTime),
+        schedule: schedulePDA(salonPDA, slotTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const freed = await program.account.daySchedule.fetch(schedulePDA(salonPDA, slotTime));
+    expect(freed.slots[firstSlot]).to.equal(0);
+
+    await program.methods
+      .setChairs(1)
+      .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+  });
+
+  it("Only books inside business hours and outside closures", async () => {
+    const [hoursSalonPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("salon"), hoursSalonOwner.publicKey.toBuffer(), locationSeed(0)],
+      program.programId
+    );
+    const [salonHoursPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hours"), hoursSalonPDA.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .registerSalon("Opening Hours Studio", [
+        {
+          id: 1,
+          name: "Blow Dry",
+          priceLamports: new anchor.BN(0.05 * LAMPORTS_PER_SOL),
+          durationMinutes: 60,
+        },
+      ])
+      .accounts({
+        platform: platformPDA,
+        ownerRegistry: ownerRegistryPDA(hoursSalonOwner.publicKey),
+        salon: hoursSalonPDA,
+        owner: hoursSalonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([hoursSalonOwner])
+      .rpc();
+
+    // Monday to Friday 09:00-18:00 at UTC+1, closed on weekends
+    const weekday = { openMinute: 9 * 60, closeMinute: 18 * 60 };
+    const closed = { openMinute: 0, closeMinute: 0 };
+    await program.methods
+      .setBusinessHours(60, [weekday, weekday, weekday, weekday, weekday, closed, closed])
+      .accounts({
+        salon: hoursSalonPDA,
+        salonHours: salonHoursPDA,
+        owner: hoursSalonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([hoursSalonOwner])
+      .rpc();
+
+    // Midnight UTC of the first Monday at least a week away (1970-01-01 was a Thursday)
+    const today = Math.floor(Date.now() / 1000 / 86400);
+    const monday = (today + 7 + ((7 - ((today + 3) % 7)) % 7)) * 86400;
+    const localHour = (day: number, hour: number) => monday + day * 86400 + (hour - 1) * 3600;
+
+    const book = (time: number) =>
+      program.methods
+        .createReservation(Buffer.from([1]), new anchor.BN(time))
+        .accounts({
+          platform: platformPDA,
+          salon: hoursSalonPDA,
+          reservation: PublicKey.findProgramAddressSync(
+            [
+              Buffer.from("reservation"),
+              client.publicKey.toBuffer(),
+              hoursSalonPDA.toBuffer(),
+              new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+            ],
+            program.programId
+          )[0],
+          schedule: schedulePDA(hoursSalonPDA, time, 60),
+          salonHours: salonHoursPDA,
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([client])
+        .rpc();
+    const expectRejected = async (time: number, code: string) => {
+      let rejected = false;
+      try {
+        await book(time);
+      } catch (err: any) {
+        rejected = true;
+        expect(err.error.errorCode.code).to.equal(code);
+      }
+      expect(rejected).to.be.true;
+    };
+
+    await book(localHour(0, 10));
+    await expectRejected(localHour(0, 8), "OutsideBusinessHours");
+    await expectRejected(localHour(0, 17) + 30 * 60, "OutsideBusinessHours"); // Ends 18:30
+    await expectRejected(localHour(6, 10), "OutsideBusinessHours"); // Sunday
+
+    // Block all of Tuesday
+    const tuesdayStart = localHour(1, 0);
+    await program.methods
+      .addClosure(new anchor.BN(tuesdayStart), new anchor.BN(tuesdayStart + 86400))
+      .accounts({
+        salon: hoursSalonPDA,
+        salonHours: salonHoursPDA,
+        owner: hoursSalonOwner.publicKey,
+      })
+      .signers([hoursSalonOwner])
+      .rpc();
+    await expectRejected(localHour(1, 10), "SalonClosed");
+
+    await program.methods
+      .removeClosure(new anchor.BN(tuesdayStart))
+      .accounts({
+        salon: hoursSalonPDA,
+        salonHours: salonHoursPDA,
+        owner: hoursSalonOwner.publicKey,
+      })
+      .signers([hoursSalonOwner])
+      .rpc();
+    await book(localHour(1, 10));
+
+    const hours = await program.account.salonHours.fetch(salonHoursPDA);
+    expect(hours.utcOffsetMinutes).to.equal(60);
+    expect(hours.closures.length).to.equal(0);
+  });
+
+  it("Reschedules into a new reservation with the salon's fee and approval", async () => {
+    const day = Math.floor(appointmentTime / 86400);
+    const oldTime = (day + 4) * 86400 + 14 * 3600;
+    const newTime = (day + 5) * 86400 + 14 * 3600;
+    const reservationAt = (time: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("reservation"),
+          client.publicKey.toBuffer(),
+          salonPDA.toBuffer(),
+          new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(oldTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: reservationAt(oldTime),
+        schedule: schedulePDA(salonPDA, oldTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // 10% fee, salon must co-sign
+    await program.methods
+      .setReschedulePolicy({
+        minNoticeSecs: new anchor.BN(24 * 60 * 60),
+        feeBps: 1_000,
+        requiresSalonApproval: true,
+      })
+      .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+
+    const reschedule = (signers: Keypair[]) =>
+      program.methods
+        .rescheduleReservation(new anchor.BN(newTime))
+        .accounts({
+          reservation: reservationAt(oldTime),
+          newReservation: reservationAt(newTime),
+          salon: salonPDA,
+          schedule: schedulePDA(salonPDA, oldTime),
+          newSchedule: schedulePDA(salonPDA, newTime),
+          client: client.publicKey,
+          salonOwner: salonOwner.publicKey,
+          payoutWallet: salonOwner.publicKey,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers(signers)
+        .rpc();
+
+    let rejected = false;
+    try {
+      await reschedule([client]);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("SalonApprovalRequired");
+    }
+    expect(rejected).to.be.true;
+
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+    await reschedule([client, salonOwner]);
+
+    const oldReservation = await program.account.reservation.fetch(reservationAt(oldTime));
+    const newReservation = await program.account.reservation.fetch(reservationAt(newTime));
+    expect(oldReservation.status.rescheduled).to.not.be.undefined;
+    expect(newReservation.status.confirmed).to.not.be.undefined;
+    expect(newReservation.amount.toNumber()).to.equal(0.09 * LAMPORTS_PER_SOL);
+    expect(newReservation.appointmentTime.toNumber()).to.equal(newTime);
+
+    // Fee minus commission went to the salon
+    const fee = 0.01 * LAMPORTS_PER_SOL;
+    const platform = await program.account.platform.fetch(platformPDA);
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    expect(salonBalanceAfter - salonBalanceBefore).to.equal(
+      fee - Math.floor((fee * platform.commissionBps) / 10_000)
+    );
+
+    // The slots moved with the booking
+    const firstSlot = (14 * 3600) / 900;
+    const oldSchedule = await program.account.daySchedule.fetch(schedulePDA(salonPDA, oldTime));
+    const newSchedule = await program.account.daySchedule.fetch(schedulePDA(salonPDA, newTime));
+    expect(oldSchedule.slots[firstSlot]).to.equal(0);
+    expect(newSchedule.slots[firstSlot]).to.equal(1);
+
+    await program.methods
+      .setReschedulePolicy({
+        minNoticeSecs: new anchor.BN(24 * 60 * 60),
+        feeBps: 0,
+        requiresSalonApproval: false,
+      })
+      .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+  });
+
+  it("Lets the salon cancel with a full refund and compensation from its bond", async () => {
+    const slotTime = (Math.floor(appointmentTime / 86400) + 6) * 86400 + 10 * 3600;
+    const [bookingPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(slotTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .depositBond(new anchor.BN(0.05 * LAMPORTS_PER_SOL))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        owner: salonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(slotTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: bookingPDA,
+        schedule: schedulePDA(salonPDA, slotTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const salonCancel = (compensation: number) =>
+      program.methods
+        .salonCancelReservation(new anchor.BN(compensation))
+        .accounts({
+          reservation: bookingPDA,
+          salon: salonPDA,
+          schedule: schedulePDA(salonPDA, slotTime),
+          salonOwner: salonOwner.publicKey,
+          platform: platformPDA,
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([salonOwner])
+        .rpc();
+
+    let rejected = false;
+    try {
+      await salonCancel(0.06 * LAMPORTS_PER_SOL);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InsufficientBond");
+    }
+    expect(rejected).to.be.true;
+
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+    await salonCancel(0.01 * LAMPORTS_PER_SOL);
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceAfter - clientBalanceBefore).to.equal(0.11 * LAMPORTS_PER_SOL);
+
+    const reservation = await program.account.reservation.fetch(bookingPDA);
+    expect(reservation.status.cancelledBySalon).to.not.be.undefined;
+
+    const salon = await program.account.salon.fetch(salonPDA);
+    expect(salon.salonCancellations.toNumber()).to.equal(1);
+    expect(salon.bondLamports.toNumber()).to.equal(0.04 * LAMPORTS_PER_SOL);
+
+    await program.methods
+      .withdrawBond(salon.bondLamports)
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        owner: salonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+  });
+
+  it("Lets anyone settle a reservation left open past the settlement window", async () => {
+    const updateSettlementWindow = (secs: number) =>
+      program.methods
+        .updatePlatformConfig({
+          commissionBps: null,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: new anchor.BN(secs),
+          expiredSettlement: null,
+          crankRewardBps: null,
+          disputeWindowSecs: null,
+          autoCloseReservations: null,
+          tipFeeBps: null,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    const expiredTime = Math.floor(Date.now() / 1000) + 2;
+    const [expiredPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(expiredTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(expiredTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: expiredPDA,
+        schedule: schedulePDA(salonPDA, expiredTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    await new Promise(resolve => setTimeout(resolve, 3000));
+
+    const settle = () =>
+      program.methods
+        .settleExpiredReservation()
+        .accounts({
+          reservation: expiredPDA,
+          salon: salonPDA,
+          payoutWallet: salonOwner.publicKey,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          cranker: otherClient.publicKey,
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([otherClient])
+        .rpc();
+
+    // The default window (7 days) has not passed yet
+    let rejected = false;
+    try {
+      await settle();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("SettlementWindowOpen");
+    }
+    expect(rejected).to.be.true;
+
+    await updateSettlementWindow(0);
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+    await settle();
+
+    // Settled as completed, with the payout held for the dispute window
+    const reservation = await program.account.reservation.fetch(expiredPDA);
+    expect(reservation.status.completed).to.not.be.undefined;
+    expect(reservation.payoutHeldUntil).to.not.be.null;
+
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    expect(salonBalanceAfter).to.equal(salonBalanceBefore);
+
+    await updateSettlementWindow(7 * 24 * 60 * 60);
+  });
+
+  it("Holds payouts for the dispute window and lets the arbiter split disputes", async () => {
+    const setDisputeWindow = (secs: number) =>
+      program.methods
+        .updatePlatformConfig({
+          commissionBps: null,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: null,
+          expiredSettlement: null,
+          crankRewardBps: null,
+          disputeWindowSecs: new anchor.BN(secs),
+          autoCloseReservations: null,
+          tipFeeBps: null,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    const reservationAt = (time: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("reservation"),
+          client.publicKey.toBuffer(),
+          salonPDA.toBuffer(),
+          new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const bookAndComplete = async (time: number) => {
+      await program.methods
+        .createReservation(Buffer.from([1]), new anchor.BN(time))
+        .accounts({
+          platform: platformPDA,
+          salon: salonPDA,
+          reservation: reservationAt(time),
+          schedule: schedulePDA(salonPDA, time),
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([client])
+        .rpc();
+      await new Promise(resolve => setTimeout(resolve, 2000));
+      await program.methods
+        .completeReservation()
+        .accounts({
+          reservation: reservationAt(time),
+          salon: salonPDA,
+          operator: salonOwner.publicKey,
+          payoutWallet: salonOwner.publicKey,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([salonOwner])
+        .rpc();
+    };
+
+    // Disputed completion, split 30% client / 70% salon
+    const disputedTime = Math.floor(Date.now() / 1000) + 1;
+    await bookAndComplete(disputedTime);
+
+    await program.methods
+      .openDispute(Array.from(Buffer.alloc(32, 7)))
+      .accounts({ reservation: reservationAt(disputedTime), client: client.publicKey })
+      .signers([client])
+      .rpc();
+
+    let reservation = await program.account.reservation.fetch(reservationAt(disputedTime));
+    expect(reservation.status.disputed).to.not.be.undefined;
+
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+    await program.methods
+      .resolveDispute(3_000)
+      .accounts({
+        reservation: reservationAt(disputedTime),
+        salon: salonPDA,
+        platform: platformPDA,
+        arbiter: admin.publicKey,
+        client: client.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        treasury: treasuryWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceAfter - clientBalanceBefore).to.equal(0.03 * LAMPORTS_PER_SOL);
+
+    reservation = await program.account.reservation.fetch(reservationAt(disputedTime));
+    expect(reservation.status.disputeResolved).to.not.be.undefined;
+
+    // Undisputed completion is released by the crank once the window ends
+    await setDisputeWindow(2);
+    const releasedTime = Math.floor(Date.now() / 1000) + 1;
+    await bookAndComplete(releasedTime);
+
+    const release = () =>
+      program.methods
+        .settleExpiredReservation()
+        .accounts({
+          reservation: reservationAt(releasedTime),
+          salon: salonPDA,
+          payoutWallet: salonOwner.publicKey,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          cranker: otherClient.publicKey,
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([otherClient])
+        .rpc();
+
+    let rejected = false;
+    try {
+      await release();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("DisputeWindowOpen");
+    }
+    expect(rejected).to.be.true;
+
+    await new Promise(resolve => setTimeout(resolve, 3000));
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+    await release();
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    expect(salonBalanceAfter).to.be.greaterThan(salonBalanceBefore);
+
+    reservation = await program.account.reservation.fetch(reservationAt(releasedTime));
+    expect(reservation.payoutHeldUntil).to.be.null;
+
+    await setDisputeWindow(48 * 60 * 60);
+  });
+
+  it("Closes settled reservations and returns the rent to the client", async () => {
+    const setAutoClose = (enabled: boolean) =>
+      program.methods
+        .updatePlatformConfig({
+          commissionBps: null,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: null,
+          expiredSettlement: null,
+          crankRewardBps: null,
+          disputeWindowSecs: null,
+          autoCloseReservations: enabled,
+          tipFeeBps: null,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    const close = (reservation: PublicKey) =>
+      program.methods
+        .closeReservation()
+        .accounts({ reservation, client: client.publicKey })
+        .rpc();
+
+    // Still confirmed: rescheduled booking from the earlier test
+    const confirmedTime = (Math.floor(appointmentTime / 86400) + 5) * 86400 + 14 * 3600;
+    const [confirmedPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(confirmedTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    let rejected = false;
+    try {
+      await close(confirmedPDA);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("ReservationNotSettled");
+    }
+    expect(rejected).to.be.true;
+
+    // Cancelled in the first cancellation test
+    const rent = await provider.connection.getBalance(reservationPDA);
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+    await close(reservationPDA);
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceAfter - clientBalanceBefore).to.equal(rent);
+    expect(await provider.connection.getAccountInfo(reservationPDA)).to.be.null;
+
+    // With auto-close on, cancelling closes the reservation right away
+    await setAutoClose(true);
+    const autoCloseTime = (Math.floor(appointmentTime / 86400) + 7) * 86400 + 10 * 3600;
+    const [autoClosePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(autoCloseTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(autoCloseTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: autoClosePDA,
+        schedule: schedulePDA(salonPDA, autoCloseTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // Cancelled more than 48h ahead: the full amount plus all of the rent goes back
+    const autoCloseAmount = 0.1 * LAMPORTS_PER_SOL;
+    const autoCloseRent = await provider.connection.getMinimumBalanceForRentExemption(
+      (await provider.connection.getAccountInfo(autoClosePDA)).data.length
+    );
+    expect(await provider.connection.getBalance(autoClosePDA)).to.equal(
+      autoCloseAmount + autoCloseRent
+    );
+    const clientBalanceBeforeAutoClose = await provider.connection.getBalance(client.publicKey);
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: autoClosePDA,
+        schedule: schedulePDA(salonPDA, autoCloseTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+    expect(await provider.connection.getAccountInfo(autoClosePDA)).to.be.null;
+    const clientBalanceAfterAutoClose = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceAfterAutoClose - clientBalanceBeforeAutoClose).to.equal(
+      autoCloseAmount + autoCloseRent
+    );
+
+    // Rescheduling closes the old reservation once the escrow has moved on
+    const reservationAt = (time: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("reservation"),
+          client.publicKey.toBuffer(),
+          salonPDA.toBuffer(),
+          new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const movedTime = autoCloseTime + 86400;
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(autoCloseTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: autoClosePDA,
+        schedule: schedulePDA(salonPDA, autoCloseTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+    await program.methods
+      .rescheduleReservation(new anchor.BN(movedTime))
+      .accounts({
+        reservation: autoClosePDA,
+        newReservation: reservationAt(movedTime),
+        salon: salonPDA,
+        schedule: schedulePDA(salonPDA, autoCloseTime),
+        newSchedule: schedulePDA(salonPDA, movedTime),
+        client: client.publicKey,
+        salonOwner: salonOwner.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client, salonOwner])
+      .rpc();
+    expect(await provider.connection.getAccountInfo(autoClosePDA)).to.be.null;
+
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: reservationAt(movedTime),
+        schedule: schedulePDA(salonPDA, movedTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+    expect(await provider.connection.getAccountInfo(reservationAt(movedTime))).to.be.null;
+
+    await setAutoClose(false);
+  });
+
+  it("Registers a second salon location for the same owner", async () => {
+    const [secondSalonPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("salon"), salonOwner.publicKey.toBuffer(), locationSeed(1)],
+      program.programId
+    );
+
+    await program.methods
+      .registerSalon("Beauty Studio Downtown", [
+        {
+          id: 1,
+          name: "Haircut",
+          priceLamports: new anchor.BN(0.1 * LAMPORTS_PER_SOL),
+          durationMinutes: 30,
+        },
+      ])
+      .accounts({
+        platform: platformPDA,
+        ownerRegistry: ownerRegistryPDA(salonOwner.publicKey),
+        salon: secondSalonPDA,
+        owner: salonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    const registry = await program.account.owner.fetch(ownerRegistryPDA(salonOwner.publicKey));
+    expect(registry.wallet.toString()).to.equal(salonOwner.publicKey.toString());
+    expect(registry.locationCount).to.equal(2);
+
+    const secondSalon = await program.account.salon.fetch(secondSalonPDA);
+    expect(secondSalon.locationIndex).to.equal(1);
+    expect(secondSalon.owner.toString()).to.equal(salonOwner.publicKey.toString());
+
+    // The first location keeps its index and data
+    const firstSalon = await program.account.salon.fetch(salonPDA);
+    expect(firstSalon.locationIndex).to.equal(0);
+    expect(firstSalon.name).to.equal("Beauty Studio");
+  });
+
+  it("Books a stylist with their own capacity and splits the payout", async () => {
+    const stylist = Keypair.generate();
+    const [staffPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("staff"), salonPDA.toBuffer(), stylist.publicKey.toBuffer()],
+      program.programId
+    );
+    const setDisputeWindow = (secs: number) =>
+      program.methods
+        .updatePlatformConfig({
+          commissionBps: null,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: null,
+          expiredSettlement: null,
+          crankRewardBps: null,
+          disputeWindowSecs: new anchor.BN(secs),
+          autoCloseReservations: null,
+          tipFeeBps: null,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+    const setChairs = (chairs: number) =>
+      program.methods
+        .setChairs(chairs)
+        .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+        .signers([salonOwner])
+        .rpc();
+
+    await program.methods
+      .addStaff(stylist.publicKey, {
+        name: "Ana",
+        serviceIds: Buffer.from([1]),
+        commissionSplitBps: 4_000,
+        capacity: 1,
+      })
+      .accounts({
+        salon: salonPDA,
+        staff: staffPDA,
+        owner: salonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    const staff = await program.account.staff.fetch(staffPDA);
+    expect(staff.wallet.toString()).to.equal(stylist.publicKey.toString());
+    expect(staff.commissionSplitBps).to.equal(4_000);
+    expect(staff.isActive).to.be.true;
+
+    const time = Math.floor(Date.now() / 1000) + 2;
+    const book = (booker: Keypair) => {
+      const [reservation] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("reservation"),
+          booker.publicKey.toBuffer(),
+          salonPDA.toBuffer(),
+          new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      return program.methods
+        .createReservation(Buffer.from([1]), new anchor.BN(time))
+        .accounts({
+          platform: platformPDA,
+          salon: salonPDA,
+          reservation,
+          schedule: schedulePDA(salonPDA, time),
+          staff: staffPDA,
+          staffSchedule: schedulePDA(staffPDA, time),
+          client: booker.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([booker])
+        .rpc()
+        .then(() => reservation);
+    };
+
+    // The salon has a free chair, but the stylist is already booked
+    await setChairs(2);
+    const staffReservationPDA = await book(client);
+    let rejected = false;
+    try {
+      await book(otherClient);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("SlotUnavailable");
+    }
+    expect(rejected).to.be.true;
+    await setChairs(1);
+
+    const reservation = await program.account.reservation.fetch(staffReservationPDA);
+    expect(reservation.staff.staff.toString()).to.equal(staffPDA.toString());
+
+    await setDisputeWindow(0);
+    await new Promise(resolve => setTimeout(resolve, 3000));
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+    await program.methods
+      .completeReservation()
+      .accounts({
+        reservation: staffReservationPDA,
+        salon: salonPDA,
+        operator: salonOwner.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        staffWallet: stylist.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    const amount = 0.1 * LAMPORTS_PER_SOL;
+    const platform = await program.account.platform.fetch(platformPDA);
+    const salonShare = amount - Math.floor((amount * platform.commissionBps) / 10_000);
+    const staffPayment = Math.floor((salonShare * 4_000) / 10_000);
+    expect(await provider.connection.getBalance(stylist.publicKey)).to.equal(staffPayment);
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    expect(salonBalanceAfter - salonBalanceBefore).to.equal(salonShare - staffPayment);
+
+    await setDisputeWindow(48 * 60 * 60);
+  });
+
+  it("Lets delegated managers and receptionists run the front desk", async () => {
+    const manager = Keypair.generate();
+    const receptionist = Keypair.generate();
+    const addDelegate = (authority: Keypair, wallet: PublicKey, role: any) =>
+      program.methods
+        .addDelegate(wallet, role)
+        .accounts({ salon: salonPDA, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+    const removeDelegate = (authority: Keypair, wallet: PublicKey) =>
+      program.methods
+        .removeDelegate(wallet)
+        .accounts({ salon: salonPDA, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+    await addDelegate(salonOwner, manager.publicKey, { manager: {} });
+    await addDelegate(manager, receptionist.publicKey, { receptionist: {} });
+
+    // Managers cannot appoint other managers
+    let rejected = false;
+    try {
+      await addDelegate(manager, otherClient.publicKey, { manager: {} });
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("UnauthorizedDelegateChange");
+    }
+    expect(rejected).to.be.true;
+
+    let salon = await program.account.salon.fetch(salonPDA);
+    expect(salon.delegates.length).to.equal(2);
+
+    // The receptionist completes a booking; the payout still goes to the owner
+    const time = Math.floor(Date.now() / 1000) + 1;
+    const [delegatedPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: delegatedPDA,
+        schedule: schedulePDA(salonPDA, time),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+    await new Promise(resolve => setTimeout(resolve, 2000));
+    await program.methods
+      .completeReservation()
+      .accounts({
+        reservation: delegatedPDA,
+        salon: salonPDA,
+        operator: receptionist.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([receptionist])
+      .rpc();
+
+    const reservation = await program.account.reservation.fetch(delegatedPDA);
+    expect(reservation.status.completed).to.not.be.undefined;
+
+    // Receptionists cannot change delegates
+    rejected = false;
+    try {
+      await removeDelegate(receptionist, manager.publicKey);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("UnauthorizedDelegateChange");
+    }
+    expect(rejected).to.be.true;
+
+    await removeDelegate(salonOwner, receptionist.publicKey);
+    await removeDelegate(salonOwner, manager.publicKey);
+    salon = await program.account.salon.fetch(salonPDA);
+    expect(salon.delegates.length).to.equal(0);
+  });
+
+  it("Sends salon payouts to a separate payout wallet", async () => {
+    const coldWallet = Keypair.generate();
+    const setPayoutWallet = (wallet: PublicKey) =>
+      program.methods
+        .setPayoutWallet(wallet)
+        .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+        .signers([salonOwner])
+        .rpc();
+    const setDisputeWindow = (secs: number) =>
+      program.methods
+        .updatePlatformConfig({
+          commissionBps: null,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: null,
+          expiredSettlement: null,
+          crankRewardBps: null,
+          disputeWindowSecs: new anchor.BN(secs),
+          autoCloseReservations: null,
+          tipFeeBps: null,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    let rejected = false;
+    try {
+      await setPayoutWallet(PublicKey.default);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidPayoutWallet");
+    }
+    expect(rejected).to.be.true;
+
+    await setPayoutWallet(coldWallet.publicKey);
+    const salon = await program.account.salon.fetch(salonPDA);
+    expect(salon.payoutWallet.toString()).to.equal(coldWallet.publicKey.toString());
+
+    const time = Math.floor(Date.now() / 1000) + 1;
+    const [payoutPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: payoutPDA,
+        schedule: schedulePDA(salonPDA, time),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    await setDisputeWindow(0);
+    await new Promise(resolve => setTimeout(resolve, 2000));
+    const complete = (payoutWallet: PublicKey) =>
+      program.methods
+        .completeReservation()
+        .accounts({
+          reservation: payoutPDA,
+          salon: salonPDA,
+          operator: salonOwner.publicKey,
+          payoutWallet,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([salonOwner])
+        .rpc();
+
+    // The owner's signing key no longer receives payouts
+    let rejected = false;
+    try {
+      await complete(salonOwner.publicKey);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidPayoutWallet");
+    }
+    expect(rejected).to.be.true;
+
+    await complete(coldWallet.publicKey);
+    const amount = 0.1 * LAMPORTS_PER_SOL;
+    const platform = await program.account.platform.fetch(platformPDA);
+    const commission = Math.floor((amount * platform.commissionBps) / 10_000);
+    expect(await provider.connection.getBalance(coldWallet.publicKey)).to.equal(amount - commission);
+
+    await setDisputeWindow(48 * 60 * 60);
+    await setPayoutWallet(salonOwner.publicKey);
+  });
+
+  it("Books a bundle of services and refunds a single line item", async () => {
+    const bundleTime = (Math.floor(appointmentTime / 86400) + 8) * 86400 + 10 * 3600;
+    const [bundlePDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(bundleTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createReservation(Buffer.from([1, 2]), new anchor.BN(bundleTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: bundlePDA,
+        schedule: schedulePDA(salonPDA, bundleTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const salon = await program.account.salon.fetch(salonPDA);
+    const [cut, polish] = [1, 2].map(id => salon.services.find(service => service.id === id));
+    let reservation = await program.account.reservation.fetch(bundlePDA);
+    expect(reservation.lineItems.map(item => item.serviceId)).to.deep.equal([1, 2]);
+    expect(reservation.amount.toNumber()).to.equal(
+      cut.priceLamports.toNumber() + polish.priceLamports.toNumber()
+    );
+    expect(reservation.durationMinutes).to.equal(cut.durationMinutes + polish.durationMinutes);
+
+    const refundLineItem = (index: number) =>
+      program.methods
+        .refundLineItem(index)
+        .accounts({
+          reservation: bundlePDA,
+          salon: salonPDA,
+          schedule: schedulePDA(salonPDA, bundleTime),
+          operator: salonOwner.publicKey,
+          client: client.publicKey,
+        })
+        .signers([salonOwner])
+        .rpc();
+
+    // The polish follows the haircut in the same chair
+    const polishTime = bundleTime + cut.durationMinutes * 60;
+    const [polishPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        otherClient.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(polishTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const bookPolish = () =>
+      program.methods
+        .createReservation(Buffer.from([2]), new anchor.BN(polishTime))
+        .accounts({
+          platform: platformPDA,
+          salon: salonPDA,
+          reservation: polishPDA,
+          schedule: schedulePDA(salonPDA, polishTime),
+          client: otherClient.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([otherClient])
+        .rpc();
+
+    let rejected = false;
+    try {
+      await bookPolish();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("SlotUnavailable");
+    }
+    expect(rejected).to.be.true;
+
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+    await refundLineItem(1);
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceAfter - clientBalanceBefore).to.equal(polish.priceLamports.toNumber());
+
+    reservation = await program.account.reservation.fetch(bundlePDA);
+    expect(reservation.lineItems[1].refunded).to.be.true;
+    expect(reservation.amount.toNumber()).to.equal(cut.priceLamports.toNumber());
+
+    // The refunded service's slots can be booked again
+    await bookPolish();
+
+    // The last remaining service can only go with the whole booking
+    rejected = false;
+    try {
+      await refundLineItem(0);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidLineItems");
+    }
+    expect(rejected).to.be.true;
+
+    // Cancelling acts on what is left of the bundle
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: bundlePDA,
+        schedule: schedulePDA(salonPDA, bundleTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    reservation = await program.account.reservation.fetch(bundlePDA);
+    expect(reservation.status.cancelled).to.not.be.undefined;
+
+    // Only the haircut's slots were released; the polish booked in between keeps its own
+    const firstSlot = (10 * 3600) / 900;
+    const schedule = await program.account.daySchedule.fetch(schedulePDA(salonPDA, bundleTime));
+    expect(schedule.slots.slice(firstSlot, firstSlot + 5)).to.deep.equal([0, 0, 1, 1, 1]);
+
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: polishPDA,
+        schedule: schedulePDA(salonPDA, polishTime),
+        client: otherClient.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([otherClient])
+      .rpc();
+  });
+
+  it("Completes a reservation partially and refunds the rest", async () => {
+    const time = Math.floor(Date.now() / 1000) + 1;
+    const [partialPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: partialPDA,
+        schedule: schedulePDA(salonPDA, time),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    await new Promise(resolve => setTimeout(resolve, 2000));
+    const completePartial = (amountToSalon: number, operator: Keypair = salonOwner) =>
+      program.methods
+        .completeReservationPartial(new anchor.BN(amountToSalon))
+        .accounts({
+          reservation: partialPDA,
+          salon: salonPDA,
+          operator: operator.publicKey,
+          payoutWallet: salonOwner.publicKey,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([operator])
+        .rpc();
+
+    // The salon can't keep more than was paid, nor nothing at all
+    const amount = 0.1 * LAMPORTS_PER_SOL;
+    for (const amountToSalon of [amount + 1, 0]) {
+      let rejected = false;
+      try {
+        await completePartial(amountToSalon);
+      } catch (err: any) {
+        rejected = true;
+        expect(err.error.errorCode.code).to.equal("InvalidPartialAmount");
+      }
+      expect(rejected).to.be.true;
+    }
+
+    // A receptionist may complete in full, but not refund part of the price
+    const receptionist = Keypair.generate();
+    await program.methods
+      .addDelegate(receptionist.publicKey, { receptionist: {} })
+      .accounts({ salon: salonPDA, authority: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+    let rejected = false;
+    try {
+      await completePartial(0.06 * LAMPORTS_PER_SOL, receptionist);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("UnauthorizedPartialCompletion");
+    }
+    expect(rejected).to.be.true;
+    await program.methods
+      .removeDelegate(receptionist.publicKey)
+      .accounts({ salon: salonPDA, authority: salonOwner.publicKey })
+      .signers([salonOwner])
+      .rpc();
+
+    const amountToSalon = 0.06 * LAMPORTS_PER_SOL;
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+    await completePartial(amountToSalon);
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceAfter - clientBalanceBefore).to.equal(amount - amountToSalon);
+
+    // The salon's part is held for the dispute window as usual
+    const reservation = await program.account.reservation.fetch(partialPDA);
+    expect(reservation.status.completed).to.not.be.undefined;
+    expect(reservation.amount.toNumber()).to.equal(amountToSalon);
+    expect(reservation.payoutHeldUntil).to.not.be.null;
+  });
+
+  it("Tips the salon for a completed reservation", async () => {
+    const time = Math.floor(Date.now() / 1000) + 1;
+    const [tipPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: tipPDA,
+        schedule: schedulePDA(salonPDA, time),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const tipAmount = 0.01 * LAMPORTS_PER_SOL;
+    const tip = () =>
+      program.methods
+        .tip(new anchor.BN(tipAmount))
+        .accounts({
+          reservation: tipPDA,
+          salon: salonPDA,
+          client: client.publicKey,
+          payoutWallet: salonOwner.publicKey,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([client])
+        .rpc();
+
+    // Tips only follow a completed service
+    let rejected = false;
+    try {
+      await tip();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidReservationStatus");
+    }
+    expect(rejected).to.be.true;
+
+    await new Promise(resolve => setTimeout(resolve, 2000));
+    await program.methods
+      .completeReservation()
+      .accounts({
+        reservation: tipPDA,
+        salon: salonPDA,
+        operator: salonOwner.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    const setTipFee = (bps: number) =>
+      program.methods
+        .updatePlatformConfig({
+          commissionBps: null,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: null,
+          expiredSettlement: null,
+          crankRewardBps: null,
+          disputeWindowSecs: null,
+          autoCloseReservations: null,
+          tipFeeBps: bps,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    // 10% tip fee to the treasury, the rest to the salon
+    await setTipFee(1000);
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+    const treasuryBalanceBefore = await provider.connection.getBalance(treasuryWallet.publicKey);
+    await tip();
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    const treasuryBalanceAfter = await provider.connection.getBalance(treasuryWallet.publicKey);
+    expect(salonBalanceAfter - salonBalanceBefore).to.equal(tipAmount * 0.9);
+    expect(treasuryBalanceAfter - treasuryBalanceBefore).to.equal(tipAmount * 0.1);
+
+    const reservation = await program.account.reservation.fetch(tipPDA);
+    expect(reservation.tips.toNumber()).to.equal(tipAmount);
+
+    await setTipFee(0);
+  });
+
+  it("Releases a held payout when the client confirms the service", async () => {
+    const time = Math.floor(Date.now() / 1000) + 1;
+    const [confirmPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: confirmPDA,
+        schedule: schedulePDA(salonPDA, time),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    await new Promise(resolve => setTimeout(resolve, 2000));
+    await program.methods
+      .completeReservation()
+      .accounts({
+        reservation: confirmPDA,
+        salon: salonPDA,
+        operator: salonOwner.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    let reservation = await program.account.reservation.fetch(confirmPDA);
+    expect(reservation.payoutHeldUntil).to.not.be.null;
+
+    const confirmService = () =>
+      program.methods
+        .confirmService()
+        .accounts({
+          reservation: confirmPDA,
+          salon: salonPDA,
+          client: client.publicKey,
+          payoutWallet: salonOwner.publicKey,
+          platform: platformPDA,
+          treasury: treasuryWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([client])
+        .rpc();
+
+    // The client's confirmation ends the review window
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+    await confirmService();
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    const amount = 0.1 * LAMPORTS_PER_SOL;
+    const platform = await program.account.platform.fetch(platformPDA);
+    const commission = Math.floor((amount * platform.commissionBps) / 10_000);
+    expect(salonBalanceAfter - salonBalanceBefore).to.equal(amount - commission);
+
+    reservation = await program.account.reservation.fetch(confirmPDA);
+    expect(reservation.status.completed).to.not.be.undefined;
+    expect(reservation.payoutHeldUntil).to.be.null;
+
+    // The payout can only be released once
+    let rejected = false;
+    try {
+      await confirmService();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidReservationStatus");
+    }
+    expect(rejected).to.be.true;
+  });
+
+  it("Escrows only the deposit and takes the balance before completion", async () => {
+    const setDepositBps = (bps: number) =>
+      program.methods
+        .setDepositBps(bps)
+        .accounts({ salon: salonPDA, owner: salonOwner.publicKey })
+        .signers([salonOwner])
+        .rpc();
+
+    let rejected = false;
+    try {
+      await setDepositBps(0);
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("InvalidDeposit");
+    }
+    expect(rejected).to.be.true;
+
+    // 20% deposit on-chain
+    await setDepositBps(2000);
+
+    const depositTime = (Math.floor(appointmentTime / 86400) + 9) * 86400 + 10 * 3600;
+    const [depositPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(depositTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(depositTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: depositPDA,
+        schedule: schedulePDA(salonPDA, depositTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const price = 0.1 * LAMPORTS_PER_SOL;
+    let reservation = await program.account.reservation.fetch(depositPDA);
+    expect(reservation.amount.toNumber()).to.equal(price * 0.2);
+    expect(reservation.balanceDue.toNumber()).to.equal(price * 0.8);
+
+    const payBalance = () =>
+      program.methods
+        .payBalance()
+        .accounts({
+          reservation: depositPDA,
+          platform: platformPDA,
+          client: client.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([client])
+        .rpc();
+
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+    await payBalance();
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+    expect(clientBalanceBefore - clientBalanceAfter).to.equal(price * 0.8);
+
+    reservation = await program.account.reservation.fetch(depositPDA);
+    expect(reservation.amount.toNumber()).to.equal(price);
+    expect(reservation.balanceDue.toNumber()).to.equal(0);
+
+    rejected = false;
+    try {
+      await payBalance();
+    } catch (err: any) {
+      rejected = true;
+      expect(err.error.errorCode.code).to.equal("NoBalanceDue");
+    }
+    expect(rejected).to.be.true;
+
+    await setDepositBps(10_000);
+  });
+
+  it("Lets the client dispute a no-show settled by the crank", async () => {
+    const updateSettlement = (secs: number, expiredSettlement: object) =>
+      program.methods
+        .updatePlatformConfig({
+          commissionBps: null,
+          noShowGraceSecs: null,
+          defaultCancellationPolicy: null,
+          settlementWindowSecs: new anchor.BN(secs),
+          expiredSettlement,
+          crankRewardBps: null,
+          disputeWindowSecs: null,
+          autoCloseReservations: null,
+          tipFeeBps: null,
+        })
+        .accounts({ platform: platformPDA, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    const time = Math.floor(Date.now() / 1000) + 1;
+    const [noShowPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: noShowPDA,
+        schedule: schedulePDA(salonPDA, time),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // The salon never acts; the crank settles the booking as a no-show
+    await updateSettlement(0, { noShow: {} });
+    await new Promise(resolve => setTimeout(resolve, 2000));
+    await program.methods
+      .settleExpiredReservation()
+      .accounts({
+        reservation: noShowPDA,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        cranker: otherClient.publicKey,
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([otherClient])
+      .rpc();
+    await updateSettlement(7 * 24 * 60 * 60, { complete: {} });
+
+    let reservation = await program.account.reservation.fetch(noShowPDA);
+    expect(reservation.status.noShow).to.not.be.undefined;
+    expect(reservation.payoutHeldUntil).to.not.be.null;
+
+    // The escrow is still there for the client to contest
+    await program.methods
+      .openDispute(Array.from(Buffer.alloc(32, 9)))
+      .accounts({ reservation: noShowPDA, client: client.publicKey })
+      .signers([client])
+      .rpc();
+
+    reservation = await program.account.reservation.fetch(noShowPDA);
+    expect(reservation.status.disputed).to.not.be.undefined;
+  });
+
+  it("Cancels with a full refund while the SOL/EUR price is stale", async () => {
+    const setStaleness = (secs: number) =>
+      program.methods
+        .setPriceOracle(null, new anchor.BN(secs))
+        .accounts({
+          platform: platformPDA,
+          priceConfig: priceConfigPDA,
+          admin: admin.publicKey,
+        })
+        .signers([admin])
+        .rpc();
+
+    const refundTime = (Math.floor(appointmentTime / 86400) + 10) * 86400 + 10 * 3600;
+    const [refundPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(refundTime).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(refundTime))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: refundPDA,
+        schedule: schedulePDA(salonPDA, refundTime),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // Let the pushed price go stale
+    await setStaleness(1);
+    await new Promise(resolve => setTimeout(resolve, 2000));
+
+    const clientBalanceBefore = await provider.connection.getBalance(client.publicKey);
+    await program.methods
+      .cancelReservation()
+      .accounts({
+        reservation: refundPDA,
+        schedule: schedulePDA(salonPDA, refundTime),
+        client: client.publicKey,
+        salon: salonPDA,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        priceConfig: priceConfigPDA,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+    const clientBalanceAfter = await provider.connection.getBalance(client.publicKey);
+
+    // > 48h before the appointment no fee applies, so the price is never read
+    expect(clientBalanceAfter - clientBalanceBefore).to.equal(0.1 * LAMPORTS_PER_SOL);
+
+    await program.methods
+      .updatePrice(new anchor.BN(6_000_000))
+      .accounts({
+        platform: platformPDA,
+        priceConfig: priceConfigPDA,
+        admin: admin.publicKey,
+      })
+      .signers([admin])
+      .rpc();
+    await setStaleness(3600);
+  });
+
+  it("Keeps schedules by local day for salons off UTC", async () => {
+    const [pacificSalonPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("salon"), hoursSalonOwner.publicKey.toBuffer(), locationSeed(1)],
+      program.programId
+    );
+    const [pacificHoursPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("hours"), pacificSalonPDA.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .registerSalon("Pacific Studio", [
+        {
+          id: 1,
+          name: "Blow Dry",
+          priceLamports: new anchor.BN(0.05 * LAMPORTS_PER_SOL),
+          durationMinutes: 60,
+        },
+      ])
+      .accounts({
+        platform: platformPDA,
+        ownerRegistry: ownerRegistryPDA(hoursSalonOwner.publicKey),
+        salon: pacificSalonPDA,
+        owner: hoursSalonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([hoursSalonOwner])
+      .rpc();
+
+    // Open 09:00-18:00 every day at UTC-8
+    const offset = -8 * 60;
+    const open = { openMinute: 9 * 60, closeMinute: 18 * 60 };
+    await program.methods
+      .setBusinessHours(offset, [open, open, open, open, open, open, open])
+      .accounts({
+        salon: pacificSalonPDA,
+        salonHours: pacificHoursPDA,
+        owner: hoursSalonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([hoursSalonOwner])
+      .rpc();
+
+    // 15:30-16:30 local is 23:30-00:30 UTC, across UTC midnight
+    const localMidnight = (Math.floor(Date.now() / 1000 / 86400) + 3) * 86400 - offset * 60;
+    const time = localMidnight + 15 * 3600 + 30 * 60;
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: pacificSalonPDA,
+        reservation: PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("reservation"),
+            client.publicKey.toBuffer(),
+            pacificSalonPDA.toBuffer(),
+            new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+          ],
+          program.programId
+        )[0],
+        schedule: schedulePDA(pacificSalonPDA, time, offset),
+        salonHours: pacificHoursPDA,
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    const schedule = await program.account.daySchedule.fetch(schedulePDA(pacificSalonPDA, time, offset));
+    expect(schedule.utcOffsetMinutes).to.equal(offset);
+    expect(schedule.slots[15 * 4 + 2]).to.equal(1); // 15:30 local
+    expect(schedule.slots[16 * 4 + 1]).to.equal(1); // 16:15 local
+  });
+
+  it("Splits the salon's part of a resolved dispute with the stylist", async () => {
+    const stylist = Keypair.generate();
+    const [staffPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("staff"), salonPDA.toBuffer(), stylist.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .addStaff(stylist.publicKey, {
+        name: "Bea",
+        serviceIds: Buffer.from([1]),
+        commissionSplitBps: 4_000,
+        capacity: 1,
+      })
+      .accounts({
+        salon: salonPDA,
+        staff: staffPDA,
+        owner: salonOwner.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+
+    const time = Math.floor(Date.now() / 1000) + 1;
+    const [disputedPDA] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reservation"),
+        client.publicKey.toBuffer(),
+        salonPDA.toBuffer(),
+        new anchor.BN(time).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createReservation(Buffer.from([1]), new anchor.BN(time))
+      .accounts({
+        platform: platformPDA,
+        salon: salonPDA,
+        reservation: disputedPDA,
+        schedule: schedulePDA(salonPDA, time),
+        staff: staffPDA,
+        staffSchedule: schedulePDA(staffPDA, time),
+        client: client.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc();
+
+    // Completed with the payout held, then disputed by the client
+    await new Promise(resolve => setTimeout(resolve, 2000));
+    await program.methods
+      .completeReservation()
+      .accounts({
+        reservation: disputedPDA,
+        salon: salonPDA,
+        operator: salonOwner.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        platform: platformPDA,
+        treasury: treasuryWallet.publicKey,
+        staffWallet: stylist.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([salonOwner])
+      .rpc();
+    await program.methods
+      .openDispute(Array.from(Buffer.alloc(32, 11)))
+      .accounts({ reservation: disputedPDA, client: client.publicKey })
+      .signers([client])
+      .rpc();
+
+    const salonBalanceBefore = await provider.connection.getBalance(salonOwner.publicKey);
+    await program.methods
+      .resolveDispute(3_000)
+      .accounts({
+        reservation: disputedPDA,
+        salon: salonPDA,
+        platform: platformPDA,
+        arbiter: admin.publicKey,
+        client: client.publicKey,
+        payoutWallet: salonOwner.publicKey,
+        treasury: treasuryWallet.publicKey,
+        staffWallet: stylist.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    // 70% goes to the salon side; the stylist takes their split after commission
+    const salonShare = 0.07 * LAMPORTS_PER_SOL;
+    const platform = await program.account.platform.fetch(platformPDA);
+    const salonNet = salonShare - Math.floor((salonShare * platform.commissionBps) / 10_000);
+    const staffPayment = Math.floor((salonNet * 4_000) / 10_000);
+    expect(await provider.connection.getBalance(stylist.publicKey)).to.equal(staffPayment);
+    const salonBalanceAfter = await provider.connection.getBalance(salonOwner.publicKey);
+    expect(salonBalanceAfter - salonBalanceBefore).to.equal(salonNet - staffPayment);
+  });
+});
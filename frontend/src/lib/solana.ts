@@ -15,9 +15,19 @@ export function getPlatformPDA(): [PublicKey, number] {
   );
 }
 
-export function getSalonPDA(ownerPubkey: PublicKey): [PublicKey, number] {
+export function getOwnerRegistryPDA(ownerPubkey: PublicKey): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from('salon'), ownerPubkey.toBuffer()],
+    [Buffer.from('owner'), ownerPubkey.toBuffer()],
+    programId
+  );
+}
+
+export function getSalonPDA(ownerPubkey: PublicKey, locationIndex: number = 0): [PublicKey, number] {
+  const indexBuffer = Buffer.alloc(2);
+  indexBuffer.writeUInt16LE(locationIndex);
+
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('salon'), ownerPubkey.toBuffer(), indexBuffer],
     programId
   );
 }